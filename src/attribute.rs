@@ -0,0 +1,358 @@
+use std::{collections::HashMap, ops::Range};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::{
+    capture,
+    error::Error,
+    expression::{Expression, Reference, Scope, Value},
+    grammar::{Grammar, GrammarRule},
+    parser::Derivation,
+    terminal::Terminal,
+};
+
+/// The `Attribute` struct represents an attribute of a rule, written `name = expression` in the
+/// grammar, e.g. `count = 1 + next.count`.
+///
+/// The expression reads the captures and the attributes of the rule by their name, the attributes
+/// of the rule before it in the derivation with `parent.name` (inherited attributes) and the ones
+/// of the rule after it with `next.name` (synthesized attributes).
+///
+/// Properties:
+///
+/// * `name`: The name of the attribute.
+/// * `expression`: The expression computing its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub expression: Expression,
+}
+
+impl Attribute {
+    /// The function `parse` reads an attribute written `name = expression`.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the attribute as written in the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The `Attribute`, or a message describing why the text is not a valid attribute.
+    pub fn parse(text: &str) -> Result<Attribute, String> {
+        let (name, expression) = text
+            .split_once('=')
+            .ok_or_else(|| "expected `name = expression`".to_string())?;
+
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("invalid attribute name `{}`", name));
+        }
+        if ["parent", "next", "true", "false"].contains(&name) {
+            return Err(format!("`{}` is reserved", name));
+        }
+
+        Ok(Attribute {
+            name: name.to_string(),
+            expression: Expression::parse(expression)?,
+        })
+    }
+
+    /// The function `parse_all` reads the attributes of a rule and checks that the names they read
+    /// in the rule are captures or attributes of the rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `rule`: The `rule` parameter is the rule of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The attributes of the rule, or the attribute which is not valid with a message telling why.
+    pub fn parse_all(rule: &GrammarRule) -> Result<Vec<Attribute>, (String, String)> {
+        let captures: Vec<&str> = rule
+            .terminals
+            .iter()
+            .filter_map(|terminal| capture::split(terminal).0)
+            .collect();
+
+        let mut attributes: Vec<Attribute> = Vec::new();
+        for text in &rule.attributes {
+            let attribute = Attribute::parse(text).map_err(|message| (text.clone(), message))?;
+
+            if captures.contains(&attribute.name.as_str())
+                || attributes.iter().any(|other| other.name == attribute.name)
+            {
+                let message = format!("the name `{}` is already used", attribute.name);
+                return Err((text.clone(), message));
+            }
+            attributes.push(attribute);
+        }
+
+        for attribute in &attributes {
+            let unknown = attribute.expression.references().into_iter().find(|r| {
+                r.scope == Scope::Own
+                    && !captures.contains(&r.name.as_str())
+                    && !attributes.iter().any(|other| other.name == r.name)
+            });
+
+            if let Some(reference) = unknown {
+                let text = format!("{} = {}", attribute.name, attribute.expression);
+                return Err((text, format!("unknown name `{}`", reference.name)));
+            }
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// The `Attributes` struct represents the values of the attributes of a rule matched by the
+/// parser.
+///
+/// Properties:
+///
+/// * `set`: The name of the grammar set of the rule.
+/// * `rule`: The index of the rule in its grammar set.
+/// * `span`: The byte span of the tokens matched by the rule, empty for a rule without token.
+/// * `values`: The name and the value of each attribute, in the order of the rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Attributes {
+    pub set: String,
+    pub rule: usize,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Range<usize>,
+    #[serde(serialize_with = "serialize_values")]
+    pub values: Vec<(String, Value)>,
+}
+
+/// The function `serialize_span` serializes a byte span as an object with a start and an end.
+fn serialize_span<S: Serializer>(span: &Range<usize>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry("start", &span.start)?;
+    map.serialize_entry("end", &span.end)?;
+    map.end()
+}
+
+/// The function `serialize_values` serializes the values of the attributes as an object, in order.
+fn serialize_values<S: Serializer>(
+    values: &[(String, Value)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(values.len()))?;
+    for (name, value) in values {
+        map.serialize_entry(name, value)?;
+    }
+    map.end()
+}
+
+/// The `State` enum represents the evaluation of an attribute of a matched rule.
+#[derive(Clone)]
+enum State {
+    Evaluating,
+    Done(Value),
+}
+
+/// The `Evaluator` struct computes the attributes of the rules which matched an input, each one
+/// being computed once, when it is first needed.
+///
+/// Properties:
+///
+/// * `rules`: The rule of the grammar of each matched rule.
+/// * `attributes`: The attributes of each matched rule.
+/// * `derivation`: The rules which matched the input, with their lexemes.
+/// * `spans`: The byte span of the tokens of each matched rule.
+/// * `states`: The attributes computed or being computed for each matched rule.
+struct Evaluator<'d, T> {
+    rules: Vec<&'d GrammarRule>,
+    attributes: Vec<Vec<Attribute>>,
+    derivation: &'d [Derivation<T>],
+    spans: Vec<Range<usize>>,
+    states: Vec<HashMap<String, State>>,
+}
+
+impl<T: Terminal> Evaluator<'_, T> {
+    /// The function `value` returns the value of a name read from a matched rule, an attribute or a
+    /// capture.
+    ///
+    /// Arguments:
+    ///
+    /// * `index`: The index of the matched rule in the derivation.
+    /// * `name`: The name of the attribute or of the capture.
+    ///
+    /// Returns:
+    ///
+    /// The value, or a message describing why it cannot be computed.
+    fn value(&mut self, index: usize, name: &str) -> Result<Value, String> {
+        let Some(position) = self.attributes[index].iter().position(|a| a.name == name) else {
+            return self.capture(index, name);
+        };
+
+        match self.states[index].get(name) {
+            Some(State::Done(value)) => return Ok(value.clone()),
+            Some(State::Evaluating) => return Err(format!("`{}` depends on itself", name)),
+            None => {}
+        }
+
+        self.states[index].insert(name.to_string(), State::Evaluating);
+        let expression = self.attributes[index][position].expression.clone();
+        let value = expression.evaluate(&mut |reference| self.resolve(index, reference))?;
+        self.states[index].insert(name.to_string(), State::Done(value.clone()));
+
+        Ok(value)
+    }
+
+    /// The function `capture` returns the value of a terminal captured by a matched rule: its
+    /// value for a number, its text otherwise.
+    fn capture(&self, index: usize, name: &str) -> Result<Value, String> {
+        let (set, rule) = (&self.derivation[index].name, self.derivation[index].index);
+        let position = self.rules[index]
+            .terminals
+            .iter()
+            .position(|terminal| capture::split(terminal).0 == Some(name))
+            .ok_or_else(|| format!("{} #{} has no attribute `{}`", set, rule, name))?;
+
+        match &self.derivation[index].lexemes[position] {
+            Some(lexeme) => Value::of(&lexeme.text, lexeme.value),
+            None => Value::of("", None),
+        }
+    }
+
+    /// The function `resolve` returns the value of a reference of an expression of a matched rule.
+    fn resolve(&mut self, index: usize, reference: &Reference) -> Result<Value, String> {
+        let target = match reference.scope {
+            Scope::Own => Some(index),
+            Scope::Parent => index.checked_sub(1),
+            Scope::Next => Some(index + 1).filter(|next| *next < self.derivation.len()),
+        };
+
+        match target {
+            Some(target) => self.value(target, &reference.name),
+            None => Err(format!("`{}` has no rule to be read from", reference)),
+        }
+    }
+}
+
+/// The function `evaluate` computes the attributes of the rules which matched an input.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the grammar the input was parsed with.
+/// * `derivation`: The `derivation` parameter is the list of rules which matched the input (see
+///   `Parser::derivation`).
+///
+/// Returns:
+///
+/// The attributes of each matched rule with at least one attribute, in the order of the input, or
+/// an `Attribute` error locating the first attribute which cannot be computed.
+pub fn evaluate<T: Terminal>(
+    grammar: &Grammar,
+    derivation: &[Derivation<T>],
+) -> Result<Vec<Attributes>, Error> {
+    let mut evaluator = Evaluator {
+        rules: Vec::new(),
+        attributes: Vec::new(),
+        derivation,
+        spans: Vec::new(),
+        states: vec![HashMap::new(); derivation.len()],
+    };
+
+    // the rules without token are located at the end of the previous one
+    let mut end = 0;
+    for matched in derivation {
+        let rule = grammar
+            .sets
+            .iter()
+            .find(|set| set.name == matched.name)
+            .and_then(|set| set.rules.get(matched.index))
+            .expect("the derivation comes from the grammar");
+        // the grammar was checked when it was compiled
+        let attributes = Attribute::parse_all(rule).unwrap_or_default();
+
+        let mut spans = matched.lexemes.iter().flatten().map(|l| l.span.clone());
+        let span = match spans.next() {
+            Some(first) => first.start..spans.last().unwrap_or(first).end,
+            None => end..end,
+        };
+        end = span.end;
+
+        evaluator.rules.push(rule);
+        evaluator.attributes.push(attributes);
+        evaluator.spans.push(span);
+    }
+
+    let mut result = Vec::new();
+    for (index, matched) in derivation.iter().enumerate() {
+        let names: Vec<String> = evaluator.attributes[index]
+            .iter()
+            .map(|attribute| attribute.name.clone())
+            .collect();
+        if names.is_empty() {
+            continue;
+        }
+
+        let mut values = Vec::new();
+        for name in names {
+            let value = evaluator
+                .value(index, &name)
+                .map_err(|message| Error::Attribute {
+                    span: evaluator.spans[index].clone(),
+                    set: matched.name.clone(),
+                    rule: matched.index,
+                    name: name.clone(),
+                    message,
+                })?;
+            values.push((name, value));
+        }
+
+        result.push(Attributes {
+            set: matched.name.clone(),
+            rule: matched.index,
+            span: evaluator.spans[index].clone(),
+            values,
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, expression::Value, grammar::Grammar, report};
+
+    const GRAMMAR: &str = "S -> C\nC -> contact start:number end:number R { window = end - start; \
+                           rates = next.rates } | None\nR -> rate value:number R { window = \
+                           parent.window; rates = 1 + next.rates } | C { rates = 0 }";
+
+    fn validate(input: &str) -> report::Report {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+        report::validate(&grammar, input).unwrap()
+    }
+
+    #[test]
+    fn computes_inherited_and_synthesized_attributes() {
+        let report = validate("contact 10 30\nrate 1\nrate 2\n");
+        assert!(report.accepted, "{:?}", report.errors);
+
+        let contact = &report.attributes[0];
+        assert_eq!((contact.set.as_str(), contact.span.clone()), ("C", 0..13));
+        assert_eq!(
+            contact.values,
+            vec![
+                ("window".to_string(), Value::Number(20)),
+                ("rates".to_string(), Value::Number(2)),
+            ]
+        );
+        assert_eq!(report.attributes[2].values[0].1, Value::Number(20));
+    }
+
+    #[test]
+    fn reports_an_overflow_as_an_attribute_error() {
+        let report = validate("contact 0 9223372036854775808\nrate 1\n");
+
+        assert!(!report.accepted);
+        assert!(
+            matches!(&report.errors[..], [Error::Attribute { name, message, .. }]
+                if name == "window" && message.contains("too large")),
+            "{:?}",
+            report.errors
+        );
+    }
+}
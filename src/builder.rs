@@ -0,0 +1,520 @@
+use crate::{
+    attribute::Attribute,
+    capture, constraint,
+    error::{Error, GrammarErrorKind},
+    grammar::{Grammar, GrammarRule, GrammarSet},
+    parser::Token,
+    predicate::Predicate,
+    symbol::Symbol,
+    template::Template,
+};
+
+/// The `GrammarBuilder` struct builds a `Grammar` programmatically, checking each terminal, set and
+/// entry of a rule as it is added. The names read by the attributes and the templates are checked by
+/// `build`, once every attribute of their rule is known.
+///
+/// Properties:
+///
+/// * `grammar`: The grammar being built.
+/// * `error`: The first error found while building the grammar, returned by `build`.
+#[derive(Debug, Clone)]
+pub struct GrammarBuilder {
+    grammar: Grammar,
+    error: Option<Error>,
+}
+
+impl Default for GrammarBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GrammarBuilder {
+    /// The function `new` creates a new instance of the `GrammarBuilder` struct with an empty grammar.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `GrammarBuilder` struct is being returned.
+    pub fn new() -> GrammarBuilder {
+        GrammarBuilder {
+            grammar: Grammar {
+                sets: Vec::new(),
+                sync: Vec::new(),
+            },
+            error: None,
+        }
+    }
+
+    /// The function `set` adds a grammar set whose rules are added by the given function.
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: The `name` parameter is the name of the grammar set.
+    /// * `build`: The `build` parameter is a function adding the rules to the `SetBuilder` it is given.
+    ///
+    /// Returns:
+    ///
+    /// The `GrammarBuilder` with the new set.
+    pub fn set(mut self, name: &str, build: impl FnOnce(SetBuilder) -> SetBuilder) -> Self {
+        if self.grammar.sets.iter().any(|set| set.name == name) {
+            self.fail(Error::Grammar {
+                set: Some(name.to_string()),
+                rule: None,
+                kind: GrammarErrorKind::DuplicateSet,
+            });
+        }
+
+        let set = build(SetBuilder {
+            set: GrammarSet {
+                name: name.to_string(),
+                rules: Vec::new(),
+            },
+            error: None,
+        });
+
+        if let Some(error) = set.error {
+            self.fail(error);
+        }
+
+        self.grammar.sets.push(set.set);
+        self
+    }
+
+    /// The function `sync` adds synchronization terminals to the grammar.
+    ///
+    /// Arguments:
+    ///
+    /// * `terminals`: The `terminals` parameter is the list of terminal names.
+    ///
+    /// Returns:
+    ///
+    /// The `GrammarBuilder` with the new synchronization terminals.
+    pub fn sync<I, S>(mut self, terminals: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for terminal in terminals {
+            let terminal = terminal.into();
+            if Token::from_string(terminal.as_str()).is_none() {
+                self.fail(Error::Grammar {
+                    set: None,
+                    rule: None,
+                    kind: GrammarErrorKind::UnknownTerminal(terminal.clone()),
+                });
+            }
+
+            self.grammar.sync.push(terminal);
+        }
+
+        self
+    }
+
+    /// The function `build` returns the grammar once every set referenced by a rule is known to
+    /// exist, and every name read by an attribute or a template is a capture or an attribute of its
+    /// rule.
+    ///
+    /// Returns:
+    ///
+    /// The built `Grammar`, or the first error found while building it.
+    pub fn build(self) -> Result<Grammar, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        // the references between sets, and the names read by the attributes and the templates, can
+        // only be checked once all of them are added
+        self.grammar.to_store::<Token>()?;
+        Ok(self.grammar)
+    }
+
+    /// The function `fail` keeps the first error found while building the grammar.
+    fn fail(&mut self, error: Error) {
+        self.error.get_or_insert(error);
+    }
+}
+
+/// The `SetBuilder` struct builds the rules of a grammar set, see `GrammarBuilder::set`.
+///
+/// Properties:
+///
+/// * `set`: The grammar set being built.
+/// * `error`: The first error found while building the set.
+#[derive(Debug, Clone)]
+pub struct SetBuilder {
+    set: GrammarSet,
+    error: Option<Error>,
+}
+
+impl SetBuilder {
+    /// The function `rule` adds a rule matching the given terminals, without non-terminal.
+    ///
+    /// Arguments:
+    ///
+    /// * `terminals`: The `terminals` parameter is the list of terminal names of the rule.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the new rule.
+    pub fn rule<I, S>(mut self, terminals: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let terminals: Vec<String> = terminals.into_iter().map(Into::into).collect();
+
+        for terminal in &terminals {
+            // the terminal may be captured and followed by a constraint, e.g. `end:Number[..100]`
+            let kind = match constraint::split(capture::split(terminal).1) {
+                Ok((name, _)) if Token::from_string(name).is_some() => continue,
+                Ok(_) => GrammarErrorKind::UnknownTerminal(terminal.clone()),
+                Err(message) => GrammarErrorKind::InvalidConstraint {
+                    terminal: terminal.clone(),
+                    message,
+                },
+            };
+
+            if self.error.is_none() {
+                self.error = Some(Error::Grammar {
+                    set: Some(self.set.name.clone()),
+                    rule: Some(self.set.rules.len()),
+                    kind,
+                });
+            }
+        }
+
+        self.set.rules.push(GrammarRule {
+            terminals,
+            non_terminal: None,
+            predicates: Vec::new(),
+            symbols: Vec::new(),
+            attributes: Vec::new(),
+            template: None,
+        });
+        self
+    }
+
+    /// The function `then` sets the non-terminal of the last rule. If there is no rule yet, or if the
+    /// last rule already has a non-terminal, a new rule without terminals is added.
+    ///
+    /// Arguments:
+    ///
+    /// * `non_terminal`: The `non_terminal` parameter is the name of the grammar set following the
+    ///   terminals of the rule.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the non-terminal.
+    pub fn then(mut self, non_terminal: &str) -> Self {
+        match self.set.rules.last_mut() {
+            Some(rule) if rule.non_terminal.is_none() => {
+                rule.non_terminal = Some(non_terminal.to_string());
+            }
+            _ => self.set.rules.push(GrammarRule {
+                terminals: Vec::new(),
+                non_terminal: Some(non_terminal.to_string()),
+                predicates: Vec::new(),
+                symbols: Vec::new(),
+                attributes: Vec::new(),
+                template: None,
+            }),
+        }
+
+        self
+    }
+
+    /// The function `require` adds a predicate to the last rule, which then only matches when the
+    /// predicate holds. If there is no rule yet, a rule without terminals is added.
+    ///
+    /// Arguments:
+    ///
+    /// * `predicate`: The `predicate` parameter is the predicate, written `name: condition` over the
+    ///   captures of the rule, e.g. `ordered: start < end`.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the predicate.
+    pub fn require(mut self, predicate: &str) -> Self {
+        if self.set.rules.is_empty() {
+            self = self.empty();
+        }
+
+        let index = self.set.rules.len() - 1;
+        let rule = &mut self.set.rules[index];
+        rule.predicates.push(predicate.to_string());
+
+        if let Err(message) = Predicate::compile(predicate, rule, &self.set.name, index) {
+            if self.error.is_none() {
+                self.error = Some(Error::Grammar {
+                    set: Some(self.set.name.clone()),
+                    rule: Some(index),
+                    kind: GrammarErrorKind::InvalidPredicate {
+                        predicate: predicate.to_string(),
+                        message,
+                    },
+                });
+            }
+        }
+
+        self
+    }
+
+    /// The function `symbol` adds a symbol entry to the last rule, declaring or using identifiers
+    /// once the input is accepted. If there is no rule yet, a rule without terminals is added.
+    ///
+    /// Arguments:
+    ///
+    /// * `symbol`: The `symbol` parameter is the entry, e.g. `declare node: name`,
+    ///   `use node: from, to` or `scope`.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the symbol entry.
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        if self.set.rules.is_empty() {
+            self = self.empty();
+        }
+
+        let index = self.set.rules.len() - 1;
+        let rule = &mut self.set.rules[index];
+        rule.symbols.push(symbol.to_string());
+
+        if let Err((symbol, message)) = Symbol::parse_all(rule) {
+            if self.error.is_none() {
+                self.error = Some(Error::Grammar {
+                    set: Some(self.set.name.clone()),
+                    rule: Some(index),
+                    kind: GrammarErrorKind::InvalidSymbol { symbol, message },
+                });
+            }
+        }
+
+        self
+    }
+
+    /// The function `attribute` adds an attribute to the last rule, computed when the rule matches.
+    /// If there is no rule yet, a rule without terminals is added.
+    ///
+    /// Arguments:
+    ///
+    /// * `attribute`: The `attribute` parameter is the attribute, written `name = expression`, e.g.
+    ///   `count = 1 + next.count`.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the attribute.
+    pub fn attribute(mut self, attribute: &str) -> Self {
+        if self.set.rules.is_empty() {
+            self = self.empty();
+        }
+
+        let index = self.set.rules.len() - 1;
+        self.set.rules[index].attributes.push(attribute.to_string());
+
+        // the names it reads may be attributes added later, see `GrammarBuilder::build`
+        if let Err(message) = Attribute::parse(attribute) {
+            if self.error.is_none() {
+                self.error = Some(Error::Grammar {
+                    set: Some(self.set.name.clone()),
+                    rule: Some(index),
+                    kind: GrammarErrorKind::InvalidAttribute {
+                        attribute: attribute.to_string(),
+                        message,
+                    },
+                });
+            }
+        }
+
+        self
+    }
+
+    /// The function `emit` sets the template of the last rule, written when the input is
+    /// translated. If there is no rule yet, a rule without terminals is added.
+    ///
+    /// Arguments:
+    ///
+    /// * `template`: The `template` parameter is the output of the rule, where `${name}` is replaced
+    ///   by a capture or an attribute of the rule, e.g. `{"from": "${from}"}`.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the template.
+    pub fn emit(mut self, template: &str) -> Self {
+        if self.set.rules.is_empty() {
+            self = self.empty();
+        }
+
+        let index = self.set.rules.len() - 1;
+        self.set.rules[index].template = Some(template.to_string());
+
+        // the placeholders may be attributes added later, see `GrammarBuilder::build`
+        if let Err(message) = Template::parse(template) {
+            if self.error.is_none() {
+                self.error = Some(Error::Grammar {
+                    set: Some(self.set.name.clone()),
+                    rule: Some(index),
+                    kind: GrammarErrorKind::InvalidTemplate {
+                        template: template.to_string(),
+                        message,
+                    },
+                });
+            }
+        }
+
+        self
+    }
+
+    /// The function `empty` adds a rule matching the end of the input.
+    ///
+    /// Returns:
+    ///
+    /// The `SetBuilder` with the new rule.
+    pub fn empty(mut self) -> Self {
+        self.set.rules.push(GrammarRule {
+            terminals: Vec::new(),
+            non_terminal: None,
+            predicates: Vec::new(),
+            symbols: Vec::new(),
+            attributes: Vec::new(),
+            template: None,
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(path: &str) -> Grammar {
+        Grammar::from_path(format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path)).unwrap()
+    }
+
+    fn json(grammar: &Grammar) -> serde_json::Value {
+        serde_json::to_value(grammar).unwrap()
+    }
+
+    #[test]
+    fn builds_the_grammar_of_a_file() {
+        let grammar = GrammarBuilder::new()
+            .set("S", |set| set.then("C"))
+            .set("C", |set| {
+                set.rule(["Contact", "Identifier", "Identifier", "Number", "Number"])
+                    .then("C")
+                    .empty()
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            json(&grammar),
+            json(&load("example/simple-correct/grammar.json"))
+        );
+    }
+
+    #[test]
+    fn builds_templates_reading_attributes_added_after_them() {
+        let insert = "INSERT INTO contacts (source, target, start, end, duration) VALUES \
+                      ('${from}', '${to}', ${start}, ${end}, ${duration});";
+        let grammar = GrammarBuilder::new()
+            .set("S", |set| set.then("C").emit("BEGIN;"))
+            .set("C", |set| {
+                set.rule([
+                    "Contact",
+                    "from:Identifier",
+                    "to:Identifier",
+                    "start:Number",
+                    "end:Number",
+                ])
+                .then("C")
+                .emit(insert)
+                .attribute("duration = end - start")
+                .empty()
+                .emit("COMMIT;")
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(json(&grammar), json(&load("example/translate/grammar.txt")));
+    }
+
+    #[test]
+    fn builds_attributes_reading_attributes_added_after_them() {
+        let grammar = GrammarBuilder::new()
+            .set("S", |set| {
+                set.rule(["Rate"])
+                    .attribute("total = count + 1")
+                    .attribute("count = 1")
+            })
+            .build();
+
+        assert!(grammar.is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_names_once_built() {
+        let error = GrammarBuilder::new()
+            .set("S", |set| set.rule(["from:Identifier"]).emit("${to}"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Grammar {
+                rule: Some(0),
+                kind: GrammarErrorKind::InvalidTemplate { .. },
+                ..
+            }
+        ));
+
+        let error = GrammarBuilder::new()
+            .set("S", |set| set.rule(["Rate"]).attribute("total = count"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Grammar {
+                kind: GrammarErrorKind::InvalidAttribute { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_entries_as_they_are_added() {
+        let error = GrammarBuilder::new()
+            .set("S", |set| set.rule(["Unknown"]).emit("${"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Grammar {
+                kind: GrammarErrorKind::UnknownTerminal(_),
+                ..
+            }
+        ));
+
+        let error = GrammarBuilder::new()
+            .set("S", |set| set.rule(["Rate"]).emit("${"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Grammar {
+                kind: GrammarErrorKind::InvalidTemplate { .. },
+                ..
+            }
+        ));
+
+        let error = GrammarBuilder::new()
+            .set("S", |set| set.then("C"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Grammar {
+                kind: GrammarErrorKind::UnknownSet(_),
+                ..
+            }
+        ));
+    }
+}
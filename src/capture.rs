@@ -0,0 +1,222 @@
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+use crate::{grammar::Grammar, parser::Derivation, terminal::Terminal};
+
+/// The `Record` struct represents the data extracted from a rule matched by the parser: the text of
+/// each terminal of the rule named by a capture, e.g. `from:Identifier`.
+///
+/// Properties:
+///
+/// * `set`: The name of the grammar set of the rule.
+/// * `rule`: The index of the rule in its grammar set.
+/// * `fields`: The captured terminals of the rule, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub set: String,
+    pub rule: usize,
+    pub fields: Vec<Field>,
+}
+
+/// The `Field` struct represents a terminal captured by a rule.
+///
+/// Properties:
+///
+/// * `name`: The name of the capture.
+/// * `text`: The text of the token matched by the terminal, empty for the end of the input.
+/// * `value`: The value of the token (see `Terminal::value`), if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub text: String,
+    pub value: Option<u64>,
+}
+
+impl Serialize for Record {
+    /// A record is serialized as an object with a member per field, in the order of the rule, the
+    /// fields with a value being numbers and the other ones strings.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in &self.fields {
+            match field.value {
+                Some(value) => map.serialize_entry(&field.name, &value)?,
+                None => map.serialize_entry(&field.name, &field.text)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// The function `split` separates the name of the capture of a terminal from the terminal itself,
+/// e.g. `from:Identifier` into `from` and `Identifier`.
+///
+/// Arguments:
+///
+/// * `terminal`: The `terminal` parameter is the terminal as written in a rule.
+///
+/// Returns:
+///
+/// The name of the capture, if any, and the terminal with its constraint.
+pub fn split(terminal: &str) -> (Option<&str>, &str) {
+    match terminal.split_once(':') {
+        // a colon inside a constraint, e.g. in a pattern, is not a capture
+        Some((name, rest))
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+        {
+            (Some(name), rest)
+        }
+        _ => (None, terminal),
+    }
+}
+
+/// The function `records` extracts the records of the rules which matched an input.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the grammar the input was parsed with.
+/// * `derivation`: The `derivation` parameter is the list of rules which matched the input (see
+///   `Parser::derivation`).
+///
+/// Returns:
+///
+/// A record per matched rule with at least one capture, in the order of the input.
+pub fn records<T: Terminal>(grammar: &Grammar, derivation: &[Derivation<T>]) -> Vec<Record> {
+    let mut records = Vec::new();
+
+    for matched in derivation {
+        let Some(rule) = grammar
+            .sets
+            .iter()
+            .find(|set| set.name == matched.name)
+            .and_then(|set| set.rules.get(matched.index))
+        else {
+            continue;
+        };
+
+        let fields: Vec<Field> = rule
+            .terminals
+            .iter()
+            .zip(&matched.lexemes)
+            .filter_map(|(terminal, lexeme)| {
+                let name = split(terminal).0?;
+                Some(Field {
+                    name: name.to_string(),
+                    text: lexeme.as_ref().map_or(String::new(), |l| l.text.clone()),
+                    value: lexeme.as_ref().and_then(|l| l.value),
+                })
+            })
+            .collect();
+
+        if !fields.is_empty() {
+            records.push(Record {
+                set: matched.name.clone(),
+                rule: matched.index,
+                fields,
+            });
+        }
+    }
+
+    records
+}
+
+/// The function `to_json` converts records into a JSON array, an object per record.
+pub fn to_json(records: &[Record]) -> String {
+    serde_json::to_string_pretty(records).unwrap()
+}
+
+/// The function `to_csv` converts records into CSV, a row per record.
+///
+/// The header lists the name of every field in the order they first appear, a record without one
+/// of them leaving its cell empty.
+pub fn to_csv(records: &[Record]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for field in records.iter().flat_map(|record| &record.fields) {
+        if !columns.contains(&field.name.as_str()) {
+            columns.push(field.name.as_str());
+        }
+    }
+
+    let mut csv = String::new();
+    let header: Vec<String> = columns.iter().map(|column| escape(column)).collect();
+    csv.push_str(&header.join(","));
+    csv.push('\n');
+
+    for record in records {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                record
+                    .fields
+                    .iter()
+                    .find(|field| field.name == *column)
+                    .map_or(String::new(), |field| escape(&field.text))
+            })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// The function `escape` quotes a CSV cell containing a comma, a quote or a line break.
+fn escape(cell: &str) -> String {
+    match cell.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", cell.replace('"', "\"\"")),
+        false => cell.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR: &str = "S -> C\nC -> contact from:identifier to:identifier start:number C | \
+                           rate number number R | None\nR -> kind:rate value:number R | C";
+
+    fn extract(input: &str) -> Option<Vec<Record>> {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+        grammar.extract(input)
+    }
+
+    #[test]
+    fn splits_captures_from_terminals() {
+        assert_eq!(split("from:Identifier"), (Some("from"), "Identifier"));
+        assert_eq!(split("Identifier"), (None, "Identifier"));
+        assert_eq!(split("Identifier[/a:b/]"), (None, "Identifier[/a:b/]"));
+    }
+
+    #[test]
+    fn extracts_the_captures_of_matched_rules() {
+        let records = extract("contact A B 20\nrate 1 2\nrate 3\ncontact B C 30\n").unwrap();
+
+        // the rule without capture has no record
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].set, "C");
+        assert_eq!(
+            records[0].fields[2],
+            Field {
+                name: "start".to_string(),
+                text: "20".to_string(),
+                value: Some(20),
+            }
+        );
+        assert_eq!(records[1].fields[0].text, "rate");
+        assert!(extract("contact A 20").is_none());
+    }
+
+    #[test]
+    fn formats_records_as_json_and_csv() {
+        let records = extract("contact A B 20\nrate 1 2\nrate 3\n").unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&records)).unwrap();
+        assert_eq!(
+            json[0],
+            serde_json::json!({ "from": "A", "to": "B", "start": 20 })
+        );
+        assert_eq!(
+            to_csv(&records),
+            "from,to,start,kind,value\nA,B,20,,\n,,,rate,3\n"
+        );
+        assert_eq!(escape("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+}
@@ -0,0 +1,217 @@
+use std::fmt;
+
+use regex::Regex;
+
+/// The `Constraint` enum represents a condition on the text of a token, written between brackets
+/// after the name of a terminal in a rule, e.g. `Number[0..100]`.
+///
+/// Variants:
+///
+/// * `Range`: The value of the token is within the bounds, both included. A missing bound is not
+///   checked, e.g. `Number[..100]`. A token without value (see `Terminal::value`) never matches.
+/// * `Digits`: The text of the token has at most the given number of characters, e.g.
+///   `Number[digits<=3]`.
+/// * `Pattern`: The whole text of the token matches the regular expression, e.g.
+///   `Identifier[/[A-Z][a-z]*/]`.
+/// * `OneOf`: The text of the token is one of the listed words, e.g. `Identifier[A,B,C]`. A single
+///   word makes a literal keyword, e.g. `Identifier[node]`.
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    Range { min: Option<u64>, max: Option<u64> },
+    Digits(usize),
+    Pattern(Regex),
+    OneOf(Vec<String>),
+}
+
+impl Constraint {
+    /// The function `parse` reads a constraint written between the brackets of a terminal.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the constraint, without the brackets.
+    ///
+    /// Returns:
+    ///
+    /// The `Constraint`, or a message describing why the text is not a valid constraint.
+    pub fn parse(text: &str) -> Result<Constraint, String> {
+        let text = text.trim();
+
+        if let Some(pattern) = text
+            .strip_prefix('/')
+            .and_then(|text| text.strip_suffix('/'))
+        {
+            // the whole text of the token must match
+            return Regex::new(&format!("^(?:{})$", pattern))
+                .map(Constraint::Pattern)
+                .map_err(|error| format!("invalid regular expression: {}", error));
+        }
+
+        if let Some(digits) = text.strip_prefix("digits<=") {
+            return digits
+                .trim()
+                .parse()
+                .map(Constraint::Digits)
+                .map_err(|_| format!("invalid number of digits `{}`", digits.trim()));
+        }
+
+        if let Some((min, max)) = text.split_once("..") {
+            let bound = |bound: &str| match bound.trim() {
+                "" => Ok(None),
+                bound => bound
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| format!("invalid bound `{}`", bound)),
+            };
+            let (min, max) = (bound(min)?, bound(max)?);
+
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    return Err(format!("empty range {}..{}", min, max));
+                }
+            }
+            return Ok(Constraint::Range { min, max });
+        }
+
+        let words: Vec<String> = text
+            .split(',')
+            .map(|word| word.trim().to_string())
+            .collect();
+        if words.iter().any(String::is_empty) {
+            return Err("expected a range, `digits<=`, a /pattern/ or a list of words".to_string());
+        }
+
+        Ok(Constraint::OneOf(words))
+    }
+
+    /// The function `check` returns true if a token satisfies the constraint.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the text of the token.
+    /// * `value`: The `value` parameter is the value of the token, if any.
+    ///
+    /// Returns:
+    ///
+    /// True if the token satisfies the constraint.
+    pub fn check(&self, text: &str, value: Option<u64>) -> bool {
+        match self {
+            Constraint::Range { min, max } => value.is_some_and(|value| {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            }),
+            Constraint::Digits(digits) => text.chars().count() <= *digits,
+            Constraint::Pattern(regex) => regex.is_match(text),
+            Constraint::OneOf(words) => words.iter().any(|word| word == text),
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    /// The constraint is written as in the grammar, without the brackets.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Range { min, max } => {
+                if let Some(min) = min {
+                    write!(f, "{}", min)?;
+                }
+                write!(f, "..")?;
+                if let Some(max) = max {
+                    write!(f, "{}", max)?;
+                }
+                Ok(())
+            }
+            Constraint::Digits(digits) => write!(f, "digits<={}", digits),
+            Constraint::Pattern(regex) => {
+                let source = regex.as_str();
+                let pattern = source
+                    .strip_prefix("^(?:")
+                    .and_then(|source| source.strip_suffix(")$"))
+                    .unwrap_or(source);
+                write!(f, "/{}/", pattern)
+            }
+            Constraint::OneOf(words) => write!(f, "{}", words.join(",")),
+        }
+    }
+}
+
+/// The function `split` separates the name of a terminal from its constraint, e.g. `Number[..100]`
+/// into `Number` and the range `..100`.
+///
+/// Arguments:
+///
+/// * `terminal`: The `terminal` parameter is the terminal as written in a rule.
+///
+/// Returns:
+///
+/// The name of the terminal and its constraint, if any, or a message describing why the constraint
+/// is not valid.
+pub fn split(terminal: &str) -> Result<(&str, Option<Constraint>), String> {
+    let Some((name, constraint)) = terminal.split_once('[') else {
+        return Ok((terminal, None));
+    };
+    let constraint = constraint
+        .strip_suffix(']')
+        .ok_or_else(|| "expected `]` at the end of the constraint".to_string())?;
+
+    Ok((name, Some(Constraint::parse(constraint)?)))
+}
+
+/// The function `name` returns the name of a terminal as written in a rule, without its constraint.
+pub fn name(terminal: &str) -> &str {
+    terminal.split_once('[').map_or(terminal, |(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(constraint: &str, text: &str) -> bool {
+        let value = text.parse().ok();
+        Constraint::parse(constraint).unwrap().check(text, value)
+    }
+
+    #[test]
+    fn checks_the_bounds_of_a_range() {
+        assert!(check("0..86400", "0"));
+        assert!(check("0..86400", "86400"));
+        assert!(!check("0..86400", "86401"));
+        assert!(check("10..", "10"));
+        assert!(!check("10..", "9"));
+        assert!(check("..100", "100"));
+        assert!(!check("..100", "101"));
+        // a token without value is never in a range
+        assert!(!check("..100", "A"));
+    }
+
+    #[test]
+    fn checks_digits_patterns_and_words() {
+        assert!(check("digits<=3", "999"));
+        assert!(!check("digits<=3", "1000"));
+        assert!(check("/[A-Z][a-z]*/", "Paris"));
+        assert!(!check("/[A-Z][a-z]*/", "paris"));
+        assert!(!check("/[A-Z]/", "AB"));
+        assert!(check("A,B", "B"));
+        assert!(!check("A,B", "C"));
+    }
+
+    #[test]
+    fn rejects_invalid_constraints() {
+        assert!(Constraint::parse("10..1").is_err());
+        assert!(Constraint::parse("a..1").is_err());
+        assert!(Constraint::parse("digits<=x").is_err());
+        assert!(Constraint::parse("/(/").is_err());
+        assert!(Constraint::parse("A,,B").is_err());
+    }
+
+    #[test]
+    fn splits_and_prints_terminals() {
+        let (name, constraint) = split("Number[..100]").unwrap();
+        assert_eq!(name, "Number");
+        assert_eq!(constraint.unwrap().to_string(), "..100");
+        assert_eq!(
+            split("Identifier[/[A-Z]/]").unwrap().1.unwrap().to_string(),
+            "/[A-Z]/"
+        );
+        assert!(split("Number").unwrap().1.is_none());
+        assert!(split("Number[1..").is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{parser::Event, report::location, terminal::Terminal};
+
+/// The `Frame` struct represents a grammar set being analyzed by the parser.
+///
+/// Properties:
+///
+/// * `name`: The name of the grammar set of the rule being tried.
+/// * `index`: The index of the rule being tried in its grammar set.
+/// * `remaining`: The rules (grammar set and index) which will be tried if this one does not match.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub name: String,
+    pub index: usize,
+    pub remaining: Vec<(String, usize)>,
+}
+
+/// The `Breakpoint` enum represents a place where the debugger stops the analysis.
+///
+/// Variants:
+///
+/// * `Set`: Stops when a rule of the grammar set with the given name is tried.
+/// * `Line`: Stops when a token of the given line (starting at 1) is matched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breakpoint {
+    Set(String),
+    Line(usize),
+}
+
+impl Breakpoint {
+    /// The function `parse` reads a breakpoint: a line number or the name of a grammar set.
+    ///
+    /// Arguments:
+    ///
+    /// * `target`: The `target` parameter is the text of the breakpoint.
+    ///
+    /// Returns:
+    ///
+    /// a `Breakpoint`.
+    pub fn parse(target: &str) -> Breakpoint {
+        match target.parse() {
+            Ok(line) => Breakpoint::Line(line),
+            Err(_) => Breakpoint::Set(target.to_string()),
+        }
+    }
+}
+
+/// The `Debugger` struct is an interactive tracer of the `Parser` which stops the analysis at each
+/// token or at breakpoints, reads commands from a reader and prints to a writer, usually the
+/// standard input and output.
+///
+/// Properties:
+///
+/// * `input`: The analyzed input, used to display the line and the column of the tokens.
+/// * `stack`: The grammar sets being analyzed, from the outermost to the innermost.
+/// * `breakpoints`: The places where the analysis stops.
+/// * `stepping`: True if the analysis stops at the next token, false if it runs until a breakpoint.
+/// * `abort`: The flag set by the `quit` command, to be given to the parser (see
+///   `Parser::with_abort`).
+/// * `reader`: The source of the commands.
+/// * `writer`: The destination of the stack, the tokens and the prompts.
+pub struct Debugger<'i, R, W> {
+    input: &'i str,
+    stack: Vec<Frame>,
+    breakpoints: Vec<Breakpoint>,
+    stepping: bool,
+    abort: Arc<AtomicBool>,
+    reader: R,
+    writer: W,
+}
+
+impl<'i, R: BufRead, W: Write> Debugger<'i, R, W> {
+    /// The function `new` creates a new instance of the `Debugger` struct which stops at the first
+    /// token.
+    ///
+    /// Arguments:
+    ///
+    /// * `input`: The `input` parameter is the input analyzed by the parser.
+    /// * `breakpoints`: The `breakpoints` parameter is the initial list of breakpoints.
+    /// * `reader`: The `reader` parameter is the source of the commands, e.g. the locked standard
+    ///   input.
+    /// * `writer`: The `writer` parameter is where the debugger prints, e.g. the standard output.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Debugger` struct is being returned.
+    pub fn new(
+        input: &'i str,
+        breakpoints: Vec<Breakpoint>,
+        reader: R,
+        writer: W,
+    ) -> Debugger<'i, R, W> {
+        Debugger {
+            input,
+            stack: Vec::new(),
+            breakpoints,
+            stepping: true,
+            abort: Arc::new(AtomicBool::new(false)),
+            reader,
+            writer,
+        }
+    }
+
+    /// The function `abort_flag` returns the flag set when the analysis is stopped with the `quit`
+    /// command, which the parser checks to stop the analysis.
+    pub fn abort_flag(&self) -> Arc<AtomicBool> {
+        self.abort.clone()
+    }
+
+    /// The function `on_event` updates the state of the debugger with an event of the parser and,
+    /// if the analysis must stop there, prompts for commands until it is resumed.
+    ///
+    /// Arguments:
+    ///
+    /// * `event`: The `event` parameter is the step of the analysis made by the parser.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())`, or the error of the reader or the writer.
+    pub fn on_event<T: Terminal>(&mut self, event: &Event<T>) -> io::Result<()> {
+        let stop = match event {
+            Event::Try {
+                name,
+                index,
+                remaining,
+                depth,
+            } => {
+                self.stack.truncate(*depth);
+                self.stack.push(Frame {
+                    name: name.clone(),
+                    index: *index,
+                    remaining: remaining.clone(),
+                });
+
+                self.breakpoints.contains(&Breakpoint::Set(name.clone()))
+            }
+            Event::Consume { span, .. } | Event::Mismatch { span, .. } => {
+                let (line, _) = location(self.input, span.start);
+                self.stepping || self.breakpoints.contains(&Breakpoint::Line(line))
+            }
+            Event::Backtrack { depth, .. } | Event::Accept { depth, .. } => {
+                self.stack.truncate(*depth);
+                false
+            }
+            Event::Enter { .. } | Event::Check { .. } => false,
+        };
+
+        if stop && !self.abort.load(Ordering::Relaxed) {
+            self.show(event)?;
+            self.prompt()?;
+        }
+
+        Ok(())
+    }
+
+    /// The function `show` prints the stack of grammar sets and the current token.
+    ///
+    /// Arguments:
+    ///
+    /// * `event`: The `event` parameter is the step of the analysis where the debugger stopped.
+    fn show<T: Terminal>(&mut self, event: &Event<T>) -> io::Result<()> {
+        writeln!(self.writer, "stack:")?;
+        for frame in &self.stack {
+            let remaining: Vec<String> = frame
+                .remaining
+                .iter()
+                .map(|(name, index)| format!("{} #{}", name, index))
+                .collect();
+
+            match remaining.is_empty() {
+                true => writeln!(self.writer, "  {} #{}", frame.name, frame.index)?,
+                false => writeln!(
+                    self.writer,
+                    "  {} #{} (then {})",
+                    frame.name,
+                    frame.index,
+                    remaining.join(", ")
+                )?,
+            }
+        }
+
+        match event {
+            Event::Consume { lexeme, span, .. } => {
+                let (line, column) = location(self.input, span.start);
+                writeln!(
+                    self.writer,
+                    "token: {} at {}:{}, matched",
+                    Event::<T>::describe(lexeme),
+                    line,
+                    column
+                )?;
+            }
+            Event::Mismatch {
+                lexeme,
+                span,
+                expected,
+                ..
+            } => {
+                let (line, column) = location(self.input, span.start);
+                match expected {
+                    Some(expected) => writeln!(
+                        self.writer,
+                        "token: {} at {}:{}, expected {}",
+                        Event::<T>::describe(lexeme),
+                        line,
+                        column,
+                        expected.name()
+                    )?,
+                    None => writeln!(
+                        self.writer,
+                        "token: {} at {}:{}, expected the end of the rule",
+                        Event::<T>::describe(lexeme),
+                        line,
+                        column
+                    )?,
+                }
+            }
+            Event::Try { name, index, .. } => {
+                writeln!(self.writer, "breakpoint: try {} #{}", name, index)?
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// The function `prompt` reads and runs commands from the reader until the analysis is resumed.
+    fn prompt(&mut self) -> io::Result<()> {
+        loop {
+            write!(self.writer, "(debug) ")?;
+            self.writer.flush()?;
+
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                // no more commands, run until the end of the analysis
+                self.stepping = false;
+                self.breakpoints.clear();
+                return writeln!(self.writer);
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] | ["s"] | ["step"] => {
+                    self.stepping = true;
+                    return Ok(());
+                }
+                ["c"] | ["continue"] => {
+                    self.stepping = false;
+                    return Ok(());
+                }
+                ["b"] | ["break"] => {
+                    for breakpoint in &self.breakpoints {
+                        match breakpoint {
+                            Breakpoint::Set(name) => writeln!(self.writer, "  {}", name)?,
+                            Breakpoint::Line(line) => writeln!(self.writer, "  line {}", line)?,
+                        }
+                    }
+                }
+                ["b", target] | ["break", target] => {
+                    self.breakpoints.push(Breakpoint::parse(target));
+                }
+                ["d", target] | ["delete", target] => {
+                    let breakpoint = Breakpoint::parse(target);
+                    self.breakpoints.retain(|b| *b != breakpoint);
+                }
+                ["q"] | ["quit"] => {
+                    self.abort.store(true, Ordering::Relaxed);
+                    return Ok(());
+                }
+                _ => {
+                    writeln!(self.writer, "commands:")?;
+                    writeln!(
+                        self.writer,
+                        "  step (s)             stop at the next token (default)"
+                    )?;
+                    writeln!(
+                        self.writer,
+                        "  continue (c)         run until the next breakpoint"
+                    )?;
+                    writeln!(
+                        self.writer,
+                        "  break (b) [SET|LINE] add a breakpoint, or list them"
+                    )?;
+                    writeln!(self.writer, "  delete (d) SET|LINE  remove a breakpoint")?;
+                    writeln!(self.writer, "  quit (q)             stop the analysis")?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::Grammar;
+
+    const INPUT: &str = "contact A\ncontact B\n";
+
+    /// The function `session` runs the debugger over `INPUT` with the given commands, and returns
+    /// whether the input was accepted, whether the analysis was aborted and what was printed.
+    fn session(commands: &str, breakpoints: Vec<Breakpoint>) -> (bool, bool, String) {
+        let grammar = Grammar::from_text("S -> C\nC -> contact identifier C | None")
+            .unwrap()
+            .compile()
+            .unwrap();
+        let mut output = Vec::new();
+        let mut debugger = Debugger::new(INPUT, breakpoints, commands.as_bytes(), &mut output);
+        let abort = debugger.abort_flag();
+
+        let mut parser = grammar
+            .parser(INPUT)
+            .with_tracer(Box::new(|event| debugger.on_event(event).unwrap()))
+            .with_abort(abort);
+        let accepted = parser.parse();
+        let aborted = parser.aborted();
+        drop(parser);
+
+        (accepted, aborted, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn steps_through_the_tokens() {
+        let (accepted, aborted, output) = session("s\nstep\n\nc\n", Vec::new());
+
+        assert!(accepted && !aborted);
+        assert_eq!(output.matches("(debug) ").count(), 4);
+        assert!(output.starts_with(
+            "stack:\n  S #0\n  C #0 (then C #1)\ntoken: Contact \"contact\" at 1:1, matched\n"
+        ));
+        assert!(output.contains("token: Identifier \"A\" at 1:9, matched\n(debug) "));
+        assert!(output.contains("token: Contact \"contact\" at 2:1, matched\n(debug) "));
+        assert!(output.contains("token: Identifier \"B\" at 2:9, matched\n(debug) "));
+    }
+
+    #[test]
+    fn continues_until_a_breakpoint() {
+        let (accepted, aborted, output) = session("c\n", Vec::new());
+
+        // the analysis only stops at the first token
+        assert!(accepted && !aborted);
+        assert_eq!(output.matches("(debug) ").count(), 1);
+
+        let (accepted, _, output) = session("c\nc\nc\nc\n", vec![Breakpoint::Line(2)]);
+
+        assert!(accepted);
+        assert_eq!(output.matches("(debug) ").count(), 4);
+        assert!(!output.contains("\"A\""));
+        assert!(output.contains("token: Identifier \"B\" at 2:9, matched\n"));
+    }
+
+    #[test]
+    fn stops_at_a_set_breakpoint_added_from_the_prompt() {
+        let (accepted, _, output) = session("b C\nc\nq\n", Vec::new());
+
+        assert!(!accepted);
+        assert!(output.contains("breakpoint: try C #0\n"));
+    }
+
+    #[test]
+    fn aborts_the_analysis_on_quit() {
+        let (accepted, aborted, output) = session("q\n", Vec::new());
+
+        assert!(!accepted && aborted);
+        assert_eq!(output.matches("(debug) ").count(), 1);
+    }
+
+    #[test]
+    fn runs_to_the_end_without_commands() {
+        let (accepted, aborted, output) = session("", Vec::new());
+
+        assert!(accepted && !aborted);
+        assert!(output.ends_with("(debug) \n"));
+    }
+}
@@ -0,0 +1,312 @@
+use std::{fmt, io, ops::Range, path::PathBuf, sync::Arc};
+
+use crate::parser::Token;
+
+/// The `Error` enum represents the errors returned by the functions of the crate.
+///
+/// Variants:
+///
+/// * `Io`: The file at `path` could not be read.
+/// * `GrammarSyntax`: The grammar is not valid JSON or does not have the expected structure. The
+///   `path` of the grammar file is known when the grammar was read from a file.
+/// * `Grammar`: The grammar is valid JSON but is not a valid grammar, see `GrammarErrorKind`. The
+///   error is located by the name of the grammar set and the index of the rule, if any.
+/// * `Lex`: The input contains text at the given byte span which is not a token.
+/// * `Syntax`: The token `found` at the given byte span, or the end of the input if it is `None`,
+///   cannot extend the input read so far into a sentence accepted by the grammar, only the
+///   `expected` tokens can.
+/// * `Constraint`: The token `found` at the given byte span is of an expected kind, but its `text`
+///   does not satisfy the constraints of the `expected` terminals, e.g. `Number[..100]`.
+/// * `Predicate`: The token at the given byte span completes the rule `rule` of the grammar set
+///   `set`, but the predicate `name` of the rule does not hold, and no other rule matches.
+/// * `Undeclared`: The input is accepted by the grammar, but the identifier `name` at the given byte
+///   span is used in the `namespace` before being declared in it (see `Symbol`).
+/// * `Redeclared`: The input is accepted by the grammar, but the identifier `name` at the given byte
+///   span is declared in the `namespace` of a scope where it was already declared at `previous`.
+/// * `Attribute`: The input is accepted by the grammar, but the attribute `name` of the rule `rule`
+///   of the grammar set `set`, matching the tokens at the given byte span, cannot be computed.
+#[derive(Debug, Clone)]
+pub enum Error {
+    Io {
+        path: PathBuf,
+        source: Arc<io::Error>,
+    },
+    GrammarSyntax {
+        path: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Grammar {
+        set: Option<String>,
+        rule: Option<usize>,
+        kind: GrammarErrorKind,
+    },
+    Lex {
+        span: Range<usize>,
+    },
+    Syntax {
+        span: Range<usize>,
+        found: Option<Token>,
+        expected: Vec<Token>,
+    },
+    Constraint {
+        span: Range<usize>,
+        found: Token,
+        text: String,
+        expected: Vec<String>,
+    },
+    Predicate {
+        span: Range<usize>,
+        set: String,
+        rule: usize,
+        name: String,
+    },
+    Undeclared {
+        span: Range<usize>,
+        namespace: String,
+        name: String,
+    },
+    Redeclared {
+        span: Range<usize>,
+        namespace: String,
+        name: String,
+        previous: Range<usize>,
+    },
+    Attribute {
+        span: Range<usize>,
+        set: String,
+        rule: usize,
+        name: String,
+        message: String,
+    },
+}
+
+/// The `GrammarErrorKind` enum represents the reasons why a grammar is not valid.
+///
+/// Variants:
+///
+/// * `UnknownTerminal`: A rule or the `sync` list uses a terminal which is not a token.
+/// * `UnknownSet`: A rule references a grammar set which does not exist.
+/// * `DuplicateSet`: Two grammar sets have the same name.
+/// * `Unproductive`: The grammar set cannot produce a finite input, each of its rules continuing
+///   with another set forever.
+/// * `InvalidConstraint`: The constraint written after a terminal cannot be read, the `message`
+///   telling why.
+/// * `DuplicateCapture`: Two terminals of a rule are captured with the same name.
+/// * `InvalidAttribute`: The `attribute` of a rule cannot be read or reads an unknown name, the
+///   `message` telling why.
+/// * `InvalidPredicate`: The `predicate` of a rule cannot be read or reads something else than the
+///   captures of the rule, the `message` telling why.
+/// * `InvalidSymbol`: The `symbol` entry of a rule cannot be read or names an unknown capture, the
+///   `message` telling why.
+/// * `InvalidTemplate`: The `template` of a rule cannot be read or names something else than the
+///   captures and the attributes of the rule, the `message` telling why.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrammarErrorKind {
+    UnknownTerminal(String),
+    UnknownSet(String),
+    DuplicateSet,
+    Unproductive,
+    InvalidConstraint { terminal: String, message: String },
+    DuplicateCapture(String),
+    InvalidAttribute { attribute: String, message: String },
+    InvalidPredicate { predicate: String, message: String },
+    InvalidSymbol { symbol: String, message: String },
+    InvalidTemplate { template: String, message: String },
+}
+
+impl Error {
+    /// The function `io` creates an `Io` error for the file at the given path.
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Error {
+        Error::Io {
+            path: path.into(),
+            source: Arc::new(source),
+        }
+    }
+
+    /// The function `grammar_syntax` creates a `GrammarSyntax` error from a JSON error.
+    pub fn grammar_syntax(path: Option<PathBuf>, source: serde_json::Error) -> Error {
+        // the position is kept apart from the message
+        let message = source.to_string();
+        let suffix = format!(" at line {} column {}", source.line(), source.column());
+
+        Error::GrammarSyntax {
+            path,
+            line: source.line(),
+            column: source.column(),
+            message: message
+                .strip_suffix(&suffix)
+                .unwrap_or(&message)
+                .to_string(),
+        }
+    }
+
+    /// The function `category` returns the name of the variant of the error, as used in reports.
+    pub fn category(&self) -> &'static str {
+        match self {
+            Error::Io { .. } => "io",
+            Error::GrammarSyntax { .. } => "grammar-syntax",
+            Error::Grammar { .. } => "grammar",
+            Error::Lex { .. } => "lex",
+            Error::Syntax { .. } => "syntax",
+            Error::Constraint { .. } => "constraint",
+            Error::Predicate { .. } => "predicate",
+            Error::Undeclared { .. } => "undeclared",
+            Error::Redeclared { .. } => "redeclared",
+            Error::Attribute { .. } => "attribute",
+        }
+    }
+
+    /// The function `span` returns the byte span of the input where an error of the input (`Lex`,
+    /// `Syntax`, `Constraint`, `Predicate`, `Undeclared`, `Redeclared` or `Attribute`) is, or `None`
+    /// for the other errors.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            Error::Lex { span }
+            | Error::Syntax { span, .. }
+            | Error::Constraint { span, .. }
+            | Error::Predicate { span, .. }
+            | Error::Undeclared { span, .. }
+            | Error::Redeclared { span, .. }
+            | Error::Attribute { span, .. } => Some(span.clone()),
+            _ => None,
+        }
+    }
+
+    /// The function `offset` returns the byte offset of the input where an error of the input
+    /// starts, or 0 for the other errors.
+    pub fn offset(&self) -> usize {
+        self.span().map_or(0, |span| span.start)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "cannot read {}: {}", path.display(), source),
+            Error::GrammarSyntax {
+                path,
+                line,
+                column,
+                message,
+            } => match path {
+                Some(path) => write!(
+                    f,
+                    "invalid grammar {}:{}:{}: {}",
+                    path.display(),
+                    line,
+                    column,
+                    message
+                ),
+                None => write!(f, "invalid grammar {}:{}: {}", line, column, message),
+            },
+            Error::Grammar { set, rule, kind } => {
+                write!(f, "invalid grammar")?;
+                if let Some(set) = set {
+                    write!(f, " in {}", set)?;
+                }
+                if let Some(rule) = rule {
+                    write!(f, " #{}", rule)?;
+                }
+
+                match kind {
+                    GrammarErrorKind::UnknownTerminal(name) => {
+                        write!(f, ": unknown terminal `{}`", name)
+                    }
+                    GrammarErrorKind::UnknownSet(name) => write!(f, ": unknown set `{}`", name),
+                    GrammarErrorKind::DuplicateSet => write!(f, ": the set is defined twice"),
+                    GrammarErrorKind::Unproductive => {
+                        write!(f, ": the set cannot produce a finite input")
+                    }
+                    GrammarErrorKind::InvalidConstraint { terminal, message } => {
+                        write!(f, ": invalid constraint in `{}`: {}", terminal, message)
+                    }
+                    GrammarErrorKind::DuplicateCapture(name) => {
+                        write!(f, ": the capture `{}` is used twice", name)
+                    }
+                    GrammarErrorKind::InvalidAttribute { attribute, message } => {
+                        write!(f, ": invalid attribute `{}`: {}", attribute, message)
+                    }
+                    GrammarErrorKind::InvalidPredicate { predicate, message } => {
+                        write!(f, ": invalid predicate `{}`: {}", predicate, message)
+                    }
+                    GrammarErrorKind::InvalidSymbol { symbol, message } => {
+                        write!(f, ": invalid symbol `{}`: {}", symbol, message)
+                    }
+                    GrammarErrorKind::InvalidTemplate { template, message } => {
+                        write!(f, ": invalid template `{}`: {}", template, message)
+                    }
+                }
+            }
+            Error::Lex { .. } => write!(f, "invalid token"),
+            Error::Syntax {
+                found, expected, ..
+            } => {
+                match found {
+                    Some(token) => write!(f, "unexpected {:?}", token)?,
+                    None => write!(f, "unexpected end of input")?,
+                }
+
+                if expected.is_empty() {
+                    return Ok(());
+                }
+
+                let names: Vec<String> = expected
+                    .iter()
+                    .map(|token| format!("{:?}", token))
+                    .collect();
+                write!(f, ", expected {}", names.join(" or "))
+            }
+            Error::Constraint {
+                found,
+                text,
+                expected,
+                ..
+            } => write!(
+                f,
+                "{:?} `{}` does not satisfy {}",
+                found,
+                text,
+                expected.join(" or ")
+            ),
+            Error::Predicate {
+                set, rule, name, ..
+            } => write!(
+                f,
+                "the predicate `{}` of {} #{} does not hold",
+                name, set, rule
+            ),
+            Error::Undeclared {
+                namespace, name, ..
+            } => write!(f, "{} `{}` is not declared", namespace, name),
+            Error::Redeclared {
+                namespace, name, ..
+            } => write!(
+                f,
+                "{} `{}` is already declared in the same scope",
+                namespace, name
+            ),
+            Error::Attribute {
+                set,
+                rule,
+                name,
+                message,
+                ..
+            } => write!(
+                f,
+                "cannot compute `{}` of {} #{}: {}",
+                name, set, rule, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// The `Value` enum represents the value of an expression.
+///
+/// Variants:
+///
+/// * `Number`: A signed integer, e.g. the value of a captured `Number`.
+/// * `Bool`: The result of a comparison or of a logical operator.
+/// * `Text`: A string, e.g. the text of a captured `Identifier`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    Number(i64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Value {
+    /// The function `of` returns the value of a captured token: its value for a number, its text
+    /// otherwise.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the text of the token, empty for the end of the input.
+    /// * `value`: The `value` parameter is the value of the token (see `Terminal::value`), if any.
+    ///
+    /// Returns:
+    ///
+    /// The value of the token, or a message if its value is too large for a number.
+    pub fn of(text: &str, value: Option<u64>) -> Result<Value, String> {
+        match value {
+            Some(value) => i64::try_from(value)
+                .map(Value::Number)
+                .map_err(|_| format!("the number `{}` is too large", text)),
+            None => Ok(Value::Text(text.to_string())),
+        }
+    }
+
+    /// The function `kind` returns the name of the type of the value, used in error messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Bool(_) => "a boolean",
+            Value::Text(_) => "a text",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{}", number),
+            Value::Bool(bool) => write!(f, "{}", bool),
+            Value::Text(text) => write!(f, "{:?}", text),
+        }
+    }
+}
+
+/// The `Scope` enum tells which rule of the derivation a reference is read from.
+///
+/// Variants:
+///
+/// * `Own`: The rule of the expression, e.g. `start`.
+/// * `Parent`: The rule before it in the derivation, whose set references the set of the rule, e.g.
+///   `parent.end` (an inherited attribute).
+/// * `Next`: The rule after it in the derivation, matching the set ending the rule, e.g.
+///   `next.count` (a synthesized attribute).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    Own,
+    Parent,
+    Next,
+}
+
+/// The `Reference` struct represents a name read by an expression, an attribute or a capture.
+///
+/// Properties:
+///
+/// * `scope`: The rule the name is read from.
+/// * `name`: The name of the attribute or of the capture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub scope: Scope,
+    pub name: String,
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.scope {
+            Scope::Own => write!(f, "{}", self.name),
+            Scope::Parent => write!(f, "parent.{}", self.name),
+            Scope::Next => write!(f, "next.{}", self.name),
+        }
+    }
+}
+
+/// The `Expression` enum represents an expression over the captures and attributes of the rules, as
+/// written in a grammar, e.g. `1 + next.count` or `start < end`.
+///
+/// Variants:
+///
+/// * `Literal`: A number, a string between double quotes, `true` or `false`.
+/// * `Reference`: A capture or an attribute (see `Reference`).
+/// * `Not`: The logical negation `!a`.
+/// * `Negate`: The arithmetic negation `-a`.
+/// * `Binary`: An arithmetic (`+ - * / %`), comparison (`== != < <= > >=`) or logical (`&& ||`)
+///   operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Literal(Value),
+    Reference(Reference),
+    Not(Box<Expression>),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, &'static str, Box<Expression>),
+}
+
+/// The binary operators, from the lowest precedence to the highest one.
+const PRECEDENCE: [&[&str]; 5] = [
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<=", ">=", "<", ">"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl Expression {
+    /// The function `parse` reads an expression.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the expression.
+    ///
+    /// Returns:
+    ///
+    /// The `Expression`, or a message describing why the text is not a valid expression.
+    pub fn parse(text: &str) -> Result<Expression, String> {
+        let mut reader = Reader { text, offset: 0 };
+        let expression = reader.binary(0)?;
+
+        reader.skip();
+        match reader.offset < text.len() {
+            true => Err(format!("unexpected `{}`", &text[reader.offset..])),
+            false => Ok(expression),
+        }
+    }
+
+    /// The function `references` returns the references read by the expression, in order.
+    pub fn references(&self) -> Vec<&Reference> {
+        match self {
+            Expression::Literal(_) => Vec::new(),
+            Expression::Reference(reference) => vec![reference],
+            Expression::Not(operand) | Expression::Negate(operand) => operand.references(),
+            Expression::Binary(left, _, right) => {
+                let mut references = left.references();
+                references.extend(right.references());
+                references
+            }
+        }
+    }
+
+    /// The function `evaluate` computes the value of the expression.
+    ///
+    /// Arguments:
+    ///
+    /// * `resolve`: The `resolve` parameter returns the value of a reference, or a message if it
+    ///   cannot be read.
+    ///
+    /// Returns:
+    ///
+    /// The value, or a message describing why it cannot be computed, e.g. a division by zero or an
+    /// overflow.
+    pub fn evaluate(
+        &self,
+        resolve: &mut dyn FnMut(&Reference) -> Result<Value, String>,
+    ) -> Result<Value, String> {
+        match self {
+            Expression::Literal(value) => Ok(value.clone()),
+            Expression::Reference(reference) => resolve(reference),
+            Expression::Not(operand) => match operand.evaluate(resolve)? {
+                Value::Bool(bool) => Ok(Value::Bool(!bool)),
+                value => Err(format!("cannot negate {}", value.kind())),
+            },
+            Expression::Negate(operand) => match operand.evaluate(resolve)? {
+                Value::Number(number) => number
+                    .checked_neg()
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("the negation of {} overflows", number)),
+                value => Err(format!("cannot negate {}", value.kind())),
+            },
+            Expression::Binary(left, operator, right) => {
+                let left = left.evaluate(resolve)?;
+
+                // the logical operators only evaluate the right operand when needed
+                match (*operator, &left) {
+                    ("&&", Value::Bool(false)) => return Ok(Value::Bool(false)),
+                    ("||", Value::Bool(true)) => return Ok(Value::Bool(true)),
+                    _ => {}
+                }
+
+                let right = right.evaluate(resolve)?;
+                Self::apply(&left, operator, &right)
+            }
+        }
+    }
+
+    /// The function `apply` applies a binary operator to the values of its operands.
+    fn apply(left: &Value, operator: &str, right: &Value) -> Result<Value, String> {
+        let value = match (left, operator, right) {
+            (_, "==", _) => Value::Bool(left == right),
+            (_, "!=", _) => Value::Bool(left != right),
+            // the left operand is `true` for `&&` and `false` for `||`, see `evaluate`
+            (Value::Bool(_), "&&" | "||", Value::Bool(b)) => Value::Bool(*b),
+            (Value::Number(_), "/" | "%", Value::Number(0)) => {
+                return Err("division by zero".to_string())
+            }
+            (Value::Number(a), "+" | "-" | "*" | "/" | "%", Value::Number(b)) => {
+                let result = match operator {
+                    "+" => a.checked_add(*b),
+                    "-" => a.checked_sub(*b),
+                    "*" => a.checked_mul(*b),
+                    "/" => a.checked_div(*b),
+                    _ => a.checked_rem(*b),
+                };
+                match result {
+                    Some(number) => Value::Number(number),
+                    None => return Err(format!("`{} {} {}` overflows", a, operator, b)),
+                }
+            }
+            (Value::Number(a), _, Value::Number(b)) => match operator {
+                "<" => Value::Bool(a < b),
+                "<=" => Value::Bool(a <= b),
+                ">" => Value::Bool(a > b),
+                ">=" => Value::Bool(a >= b),
+                _ => return Err(Self::mismatch(left, operator, right)),
+            },
+            (Value::Text(a), "+", Value::Text(b)) => Value::Text(format!("{}{}", a, b)),
+            (Value::Text(a), _, Value::Text(b)) => match operator {
+                "<" => Value::Bool(a < b),
+                "<=" => Value::Bool(a <= b),
+                ">" => Value::Bool(a > b),
+                ">=" => Value::Bool(a >= b),
+                _ => return Err(Self::mismatch(left, operator, right)),
+            },
+            _ => return Err(Self::mismatch(left, operator, right)),
+        };
+
+        Ok(value)
+    }
+
+    /// The function `mismatch` describes an operator applied to values of the wrong type.
+    fn mismatch(left: &Value, operator: &str, right: &Value) -> String {
+        format!(
+            "cannot apply `{}` to {} and {}",
+            operator,
+            left.kind(),
+            right.kind()
+        )
+    }
+}
+
+impl fmt::Display for Expression {
+    /// The expression is written with parentheses around each operation, so that it can be read back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Literal(value) => write!(f, "{}", value),
+            Expression::Reference(reference) => write!(f, "{}", reference),
+            Expression::Not(operand) => write!(f, "!{}", operand),
+            Expression::Negate(operand) => write!(f, "-{}", operand),
+            Expression::Binary(left, operator, right) => {
+                write!(f, "({} {} {})", left, operator, right)
+            }
+        }
+    }
+}
+
+/// The `Reader` struct reads an expression from its text (recursive descent).
+///
+/// Properties:
+///
+/// * `text`: The text of the expression.
+/// * `offset`: The byte offset of the next character to read.
+struct Reader<'t> {
+    text: &'t str,
+    offset: usize,
+}
+
+impl Reader<'_> {
+    /// The function `binary` reads the operations of the given precedence level and the higher ones.
+    fn binary(&mut self, level: usize) -> Result<Expression, String> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.unary();
+        };
+
+        let mut left = self.binary(level + 1)?;
+        while let Some(operator) = self.operator(operators) {
+            let right = self.binary(level + 1)?;
+            left = Expression::Binary(Box::new(left), operator, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    /// The function `operator` reads one of the given operators, if it comes next.
+    fn operator(&mut self, operators: &[&'static str]) -> Option<&'static str> {
+        self.skip();
+        let operator = operators
+            .iter()
+            .find(|operator| self.rest().starts_with(**operator))?;
+
+        self.offset += operator.len();
+        Some(operator)
+    }
+
+    /// The function `unary` reads a negation or an operand.
+    fn unary(&mut self) -> Result<Expression, String> {
+        self.skip();
+
+        // `!=` is a binary operator, only a single `!` is a negation
+        if self.rest().starts_with('!') && !self.rest().starts_with("!=") {
+            self.offset += 1;
+            return Ok(Expression::Not(Box::new(self.unary()?)));
+        }
+        if self.rest().starts_with('-') {
+            self.offset += 1;
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+
+        self.primary()
+    }
+
+    /// The function `primary` reads a literal, a reference or an expression between parentheses.
+    fn primary(&mut self) -> Result<Expression, String> {
+        let text = self.text;
+        let rest = &text[self.offset..];
+
+        match rest.chars().next() {
+            None => Err("unexpected end of the expression".to_string()),
+            Some('(') => {
+                self.offset += 1;
+                let expression = self.binary(0)?;
+                self.skip();
+                match self.rest().starts_with(')') {
+                    true => {
+                        self.offset += 1;
+                        Ok(expression)
+                    }
+                    false => Err("expected `)`".to_string()),
+                }
+            }
+            Some('"') => {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| "expected `\"` at the end of the text".to_string())?;
+                self.offset += end + 2;
+                Ok(Expression::Literal(Value::Text(
+                    rest[1..end + 1].to_string(),
+                )))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let digits = self.take(|c| c.is_ascii_digit());
+                digits
+                    .parse()
+                    .map(|number| Expression::Literal(Value::Number(number)))
+                    .map_err(|_| format!("the number `{}` is too large", digits))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.name();
+                let scope = match name.as_str() {
+                    "true" => return Ok(Expression::Literal(Value::Bool(true))),
+                    "false" => return Ok(Expression::Literal(Value::Bool(false))),
+                    "parent" => Scope::Parent,
+                    "next" => Scope::Next,
+                    _ => {
+                        return Ok(Expression::Reference(Reference {
+                            scope: Scope::Own,
+                            name,
+                        }))
+                    }
+                };
+
+                // `parent` and `next` are followed by the name of an attribute of the rule
+                if !self.rest().starts_with('.') {
+                    return Err(format!("expected `.` and a name after `{}`", name));
+                }
+                self.offset += 1;
+                let attribute = self.name();
+                if attribute.is_empty() {
+                    return Err(format!("expected a name after `{}.`", name));
+                }
+
+                Ok(Expression::Reference(Reference {
+                    scope,
+                    name: attribute,
+                }))
+            }
+            Some(c) => Err(format!("unexpected `{}`", c)),
+        }
+    }
+
+    /// The function `name` reads a name made of letters, digits and underscores.
+    fn name(&mut self) -> String {
+        self.take(|c| c.is_alphanumeric() || c == '_').to_string()
+    }
+
+    /// The function `take` reads the characters matching the predicate.
+    fn take(&mut self, predicate: impl Fn(char) -> bool) -> &str {
+        let rest = &self.text[self.offset..];
+        let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.offset += length;
+        &rest[..length]
+    }
+
+    /// The function `skip` skips the whitespace.
+    fn skip(&mut self) {
+        self.take(char::is_whitespace);
+    }
+
+    /// The function `rest` returns the text left to read.
+    fn rest(&self) -> &str {
+        &self.text[self.offset..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(text: &str) -> Result<Value, String> {
+        Expression::parse(text)
+            .unwrap()
+            .evaluate(&mut |reference| match reference.name.as_str() {
+                "max" => Value::of("9223372036854775807", Some(i64::MAX as u64)),
+                "huge" => Value::of("18446744073709551615", Some(u64::MAX)),
+                name => Err(format!("unknown `{}`", name)),
+            })
+    }
+
+    #[test]
+    fn evaluates_with_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), Ok(Value::Number(7)));
+        assert_eq!(evaluate("(1 + 2) * 3 % 4"), Ok(Value::Number(1)));
+        assert_eq!(evaluate("-2 < 1 && !false"), Ok(Value::Bool(true)));
+        assert_eq!(evaluate("\"a\" + \"b\" == \"ab\""), Ok(Value::Bool(true)));
+        // the right operand is not evaluated when the left one decides
+        assert_eq!(evaluate("false && unknown"), Ok(Value::Bool(false)));
+    }
+
+    #[test]
+    fn reports_values_which_cannot_be_computed() {
+        assert!(evaluate("1 / 0").is_err());
+        assert!(evaluate("1 + true").is_err());
+        assert!(evaluate("max + 1").is_err());
+        assert!(evaluate("-max - 2").is_err());
+        assert!(evaluate("max * 2").is_err());
+        assert_eq!(
+            evaluate("huge"),
+            Err("the number `18446744073709551615` is too large".to_string())
+        );
+        assert_eq!(evaluate("max - 1 + 1"), Ok(Value::Number(i64::MAX)));
+    }
+
+    #[test]
+    fn prints_expressions_which_read_back() {
+        let expression = Expression::parse("1 + next.count * 2 >= parent.end").unwrap();
+        assert_eq!(
+            expression.to_string(),
+            "((1 + (next.count * 2)) >= parent.end)"
+        );
+        assert_eq!(Expression::parse(&expression.to_string()), Ok(expression));
+        assert!(Expression::parse("1 +").is_err());
+        assert!(Expression::parse("next").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use crate::{
+    capture,
+    constraint::{self, Constraint},
+    error::{Error, GrammarErrorKind},
+    expression::Value,
+    grammar::{Grammar, GrammarRule},
+    parser::Token,
+    predicate::Predicate,
+    symbol::{Declaration, Symbol, SymbolTable},
+    terminal::Terminal,
+};
+
+/// The number of times the rules of a grammar set are picked and written again until the
+/// predicates and the identifiers of one of them hold.
+const ATTEMPTS: usize = 1000;
+
+/// The `Generator` struct produces random inputs accepted by a grammar, e.g. to test a program
+/// reading them.
+///
+/// Starting from a grammar set, a rule is picked at random and its terminals are written on a line,
+/// then the analysis continues with the set ending the rule. Once the input has `length` lines, the
+/// rules closest to the end of the input are preferred so that the generation always stops. A rule
+/// whose predicates do not hold over the texts written for its terminals is written again, or
+/// another rule is picked. The identifiers a rule uses are picked among the declared ones, and the
+/// ones it declares must not be declared yet in the same scope (see `SymbolTable`).
+///
+/// Properties:
+///
+/// * `grammar`: The grammar the inputs are accepted by.
+/// * `state`: The state of the pseudo-random number generator (xorshift).
+/// * `length`: The number of rules after which the generation heads to the end of the input.
+/// * `distances`: The minimal number of rules to end the input from each grammar set.
+pub struct Generator<'g> {
+    grammar: &'g Grammar,
+    state: u64,
+    length: usize,
+    distances: HashMap<String, usize>,
+}
+
+impl<'g> Generator<'g> {
+    /// The function `new` creates a new instance of the `Generator` struct.
+    ///
+    /// Arguments:
+    ///
+    /// * `grammar`: The `grammar` parameter is the grammar the inputs are accepted by.
+    /// * `seed`: The `seed` parameter is the seed of the random choices, the same seed always
+    ///   producing the same inputs.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Generator` struct is being returned.
+    pub fn new(grammar: &'g Grammar, seed: u64) -> Generator<'g> {
+        Generator {
+            grammar,
+            // the state of xorshift must not be zero
+            state: seed.wrapping_mul(0x9e3779b97f4a7c15) | 1,
+            length: 10,
+            distances: Self::distances(grammar),
+        }
+    }
+
+    /// The function `with_length` sets the number of rules after which the generation heads to the
+    /// end of the input.
+    ///
+    /// Arguments:
+    ///
+    /// * `length`: The `length` parameter is the number of rules.
+    ///
+    /// Returns:
+    ///
+    /// The `Generator` with the given length.
+    pub fn with_length(mut self, length: usize) -> Generator<'g> {
+        self.length = length;
+        self
+    }
+
+    /// The function `generate` produces a random input starting from the given grammar set.
+    ///
+    /// Arguments:
+    ///
+    /// * `start`: The `start` parameter is the name of the grammar set the input must match.
+    ///
+    /// Returns:
+    ///
+    /// The input, a line per rule with terminals, or an error if the set does not exist or cannot
+    /// produce a finite input, e.g. when the predicates of its rules never hold.
+    pub fn generate(&mut self, start: &str) -> Result<String, Error> {
+        let grammar = self.grammar;
+        let mut table = SymbolTable::new();
+        let mut lines = Vec::new();
+        let mut current = start.to_string();
+
+        for count in 0.. {
+            let error = |kind| Error::Grammar {
+                set: Some(current.clone()),
+                rule: None,
+                kind,
+            };
+
+            let set = grammar
+                .sets
+                .iter()
+                .find(|set| set.name == current)
+                .ok_or_else(|| error(GrammarErrorKind::UnknownSet(current.clone())))?;
+
+            // only the rules which can end the input are picked, the closest ones once the input
+            // is long enough
+            let distances: Vec<Option<usize>> =
+                set.rules.iter().map(|rule| self.distance(rule)).collect();
+            let minimum = distances
+                .iter()
+                .flatten()
+                .min()
+                .copied()
+                .ok_or_else(|| error(GrammarErrorKind::Unproductive))?;
+            let candidates: Vec<(usize, &GrammarRule)> = set
+                .rules
+                .iter()
+                .enumerate()
+                .zip(&distances)
+                .filter(|(_, distance)| match count < self.length {
+                    true => distance.is_some(),
+                    false => **distance == Some(minimum),
+                })
+                .map(|(rule, _)| rule)
+                .collect();
+
+            // a rule is picked and written until its predicates and its identifiers hold
+            let (rule, words, next) = (0..ATTEMPTS)
+                .find_map(|_| {
+                    let (index, rule) = candidates[self.next() as usize % candidates.len()];
+                    let (words, next) = self.words(&set.name, index, rule, &table)?;
+                    Some((rule, words, next))
+                })
+                .ok_or_else(|| error(GrammarErrorKind::Unproductive))?;
+            table = next;
+
+            let words: Vec<String> = words.into_iter().flatten().collect();
+            if !words.is_empty() {
+                lines.push(words.join(" "));
+            }
+
+            match &rule.non_terminal {
+                Some(non_terminal) => current = non_terminal.clone(),
+                None => break,
+            }
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    /// The function `words` writes a random text for each terminal of a rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `set`: The `set` parameter is the name of the grammar set of the rule.
+    /// * `index`: The `index` parameter is the index of the rule in its grammar set.
+    /// * `rule`: The `rule` parameter is the rule to write.
+    /// * `table`: The `table` parameter is the identifiers declared by the rules written before.
+    ///
+    /// Returns:
+    ///
+    /// The text of each terminal, `None` for the end of the input, with the identifiers declared
+    /// once the rule is written, or `None` if the predicates or the identifiers of the rule do not
+    /// hold over them.
+    fn words(
+        &mut self,
+        set: &str,
+        index: usize,
+        rule: &GrammarRule,
+        table: &SymbolTable,
+    ) -> Option<(Vec<Option<String>>, SymbolTable)> {
+        // the grammar was checked when it was compiled
+        let symbols = Symbol::parse_all(rule).unwrap_or_default();
+
+        // a used identifier is one of the declared ones
+        let mut words: Vec<Option<String>> = Vec::new();
+        for terminal in &rule.terminals {
+            let namespace = capture::split(terminal).0.and_then(|name| {
+                symbols.iter().find_map(|symbol| match symbol {
+                    Symbol::Use {
+                        namespace,
+                        captures,
+                    } if captures.iter().any(|capture| capture == name) => Some(namespace),
+                    _ => None,
+                })
+            });
+
+            let word = match namespace {
+                Some(namespace) => {
+                    let names = table.names(namespace);
+                    if names.is_empty() {
+                        return None;
+                    }
+                    Some(names[self.next() as usize % names.len()].to_string())
+                }
+                None => self.lexeme(terminal),
+            };
+            words.push(word);
+        }
+
+        // the predicates read the values of the terminals, as the parser does
+        let values: Vec<Result<Value, String>> = rule
+            .terminals
+            .iter()
+            .zip(&words)
+            .map(|(terminal, word)| {
+                let text = word.as_deref().unwrap_or("");
+                let name = constraint::name(capture::split(terminal).1);
+                let value = Token::from_string(name).and_then(|token| token.value(text));
+                Value::of(text, value)
+            })
+            .collect();
+
+        // the grammar was checked when it was compiled
+        let holds = rule
+            .predicates
+            .iter()
+            .all(|text| Predicate::compile(text, rule, set, index).is_ok_and(|p| p.holds(&values)));
+
+        if !holds {
+            return None;
+        }
+
+        // a declared identifier is not declared yet in the same scope, as checked by `symbol::check`
+        let mut table = table.clone();
+        let text = |name: &str| {
+            let position = rule
+                .terminals
+                .iter()
+                .position(|terminal| capture::split(terminal).0 == Some(name))?;
+            words[position].clone()
+        };
+        for symbol in &symbols {
+            if let Symbol::Declare {
+                namespace,
+                captures,
+            } = symbol
+            {
+                for name in captures.iter().filter_map(|name| text(name)) {
+                    let declaration = Declaration {
+                        namespace: namespace.clone(),
+                        name,
+                        span: 0..0,
+                    };
+                    table.declare(declaration).ok()?;
+                }
+            }
+        }
+        if symbols.contains(&Symbol::Scope) {
+            table.open(set);
+        }
+
+        Some((words, table))
+    }
+
+    /// The function `distance` returns the minimal number of rules to end the input with the given
+    /// rule, or `None` if it cannot end the input.
+    fn distance(&self, rule: &GrammarRule) -> Option<usize> {
+        match &rule.non_terminal {
+            Some(non_terminal) => self
+                .distances
+                .get(non_terminal)
+                .map(|distance| distance + 1),
+            None => Some(1),
+        }
+    }
+
+    /// The function `distances` computes the minimal number of rules to end the input from each
+    /// grammar set, the sets which cannot end it being left out.
+    fn distances(grammar: &Grammar) -> HashMap<String, usize> {
+        let mut distances: HashMap<String, usize> = HashMap::new();
+
+        // the distances can only decrease, repeat until they are stable
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for set in &grammar.sets {
+                for rule in &set.rules {
+                    let distance = match &rule.non_terminal {
+                        Some(non_terminal) => match distances.get(non_terminal) {
+                            Some(distance) => distance + 1,
+                            None => continue,
+                        },
+                        None => 1,
+                    };
+
+                    if distances.get(&set.name).is_none_or(|d| distance < *d) {
+                        distances.insert(set.name.clone(), distance);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// The function `lexeme` returns a random text matching the given terminal.
+    ///
+    /// Arguments:
+    ///
+    /// * `terminal`: The `terminal` parameter is the name of the terminal in the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The text, or `None` for the end of the input which has no text. The constraints of the
+    /// terminal are satisfied, except the patterns which are ignored.
+    fn lexeme(&mut self, terminal: &str) -> Option<String> {
+        let terminal = capture::split(terminal).1;
+        let (name, constraint) = match constraint::split(terminal) {
+            Ok(split) => split,
+            Err(_) => (constraint::name(terminal), None),
+        };
+
+        match (name, constraint) {
+            ("End", _) => None,
+            (_, Some(Constraint::OneOf(words))) => {
+                Some(words[self.next() as usize % words.len()].clone())
+            }
+            ("Number", Some(Constraint::Range { min, max })) => {
+                let min = min.unwrap_or(0);
+                let max = max.unwrap_or(min.saturating_add(99));
+                let count = (max - min).saturating_add(1).max(1);
+                Some((min + self.next() % count).to_string())
+            }
+            ("Number", Some(Constraint::Digits(digits))) => {
+                let count = 10u64.checked_pow(digits as u32).unwrap_or(u64::MAX);
+                Some((self.next() % count.min(100)).to_string())
+            }
+            ("Identifier", _) => Some(((b'A' + (self.next() % 26) as u8) as char).to_string()),
+            ("Number", _) => Some((self.next() % 100).to_string()),
+            // the keywords are written in lowercase
+            _ => Some(name.to_lowercase()),
+        }
+    }
+
+    /// The function `next` returns the next pseudo-random number (xorshift64).
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report;
+
+    /// Generates inputs with several seeds and validates each of them.
+    fn accepts_generated(text: &str) {
+        let grammar = Grammar::from_text(text).unwrap().compile().unwrap();
+
+        for seed in 0..50 {
+            let input = Generator::new(grammar.grammar(), seed)
+                .with_length(5)
+                .generate("S")
+                .unwrap();
+            let report = report::validate(&grammar, &input).unwrap();
+            assert!(
+                report.accepted,
+                "seed {}: {:?}\n{}",
+                seed, report.errors, input
+            );
+        }
+    }
+
+    #[test]
+    fn generates_inputs_satisfying_the_constraints() {
+        accepts_generated(
+            "S -> C\nC -> contact identifier[A,B] number[10..20] number[digits<=2] C | end",
+        );
+    }
+
+    #[test]
+    fn generates_inputs_satisfying_the_predicates() {
+        accepts_generated(
+            "S -> C\nC -> contact from:identifier to:identifier start:number end:number C \
+             { require ordered: start < end; require distinct: from != to } | None",
+        );
+    }
+
+    #[test]
+    fn generates_inputs_declaring_their_identifiers() {
+        accepts_generated(
+            "S -> N\nN -> identifier[node] name:identifier N { declare node: name } | C\nC -> \
+             contact from:identifier to:identifier R { use node: from, to; scope } | None\nR -> \
+             rate label:identifier R { declare rate: label } | C",
+        );
+    }
+
+    #[test]
+    fn reports_predicates_which_never_hold() {
+        let grammar = Grammar::from_text("S -> rate n:number { require never: n < 0 }").unwrap();
+
+        assert!(matches!(
+            Generator::new(&grammar, 1).generate("S"),
+            Err(Error::Grammar {
+                kind: GrammarErrorKind::Unproductive,
+                ..
+            })
+        ));
+    }
+}
@@ -1,49 +1,199 @@
 use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
+use logos::Logos;
 use serde::{Deserialize, Serialize};
 
-use crate::{parser::Token, rules::RuleStep, store::Store};
+use crate::{
+    attribute::Attribute,
+    capture::{self, Record},
+    constraint,
+    error::{Error, GrammarErrorKind},
+    monitor::Monitor,
+    notation,
+    parser::{Parser, Token},
+    predicate::Predicate,
+    rules::RuleStep,
+    store::Store,
+    symbol::Symbol,
+    template::{self, Template},
+    terminal::Terminal,
+    validator::Validator,
+};
 
 /// The `Grammar` struct represents a grammar and contains a vector of `GrammarSet` objects.
 ///
 /// Properties:
 ///
 /// * `sets`: The `sets` property is a vector of `GrammarSet` structs.
+/// * `sync`: The `sync` property is a vector of terminals on which the analysis resynchronizes after
+///   an error when all the errors are reported.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Grammar {
-    sets: Vec<GrammarSet>,
+    pub sets: Vec<GrammarSet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sync: Vec<String>,
 }
 
 impl Grammar {
-    /// The `parse` function reads a file at the given path, converts its contents to a JSON string, and
-    /// then deserializes it into a Rust data structure.
+    /// The `from_path` function reads a file at the given path, converts its contents to a JSON string,
+    /// and then deserializes it into a Rust data structure. A file which is not a JSON object is read
+    /// in the text notation instead (see `from_text`).
     ///
     /// Arguments:
     ///
-    /// * `path`: The `path` parameter is a string that represents the file path to the grammar file
-    /// that needs to be parsed.
+    /// * `path`: The `path` parameter represents the file path to the grammar file that needs to be
+    ///   parsed.
     ///
     /// Returns:
     ///
-    /// an instance of the type `Self`.
-    pub fn parse(path: &str) -> Self {
-        let grammar = std::fs::read_to_string(path).unwrap();
-        serde_json::from_str(grammar.as_str()).unwrap()
+    /// an instance of the type `Self`, or an error if the file cannot be read or is not a grammar.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let grammar = std::fs::read_to_string(path).map_err(|source| Error::io(path, source))?;
+
+        if !grammar.trim_start().starts_with('{') {
+            return notation::parse(grammar.as_str(), Some(path.to_path_buf()));
+        }
+
+        serde_json::from_str(grammar.as_str())
+            .map_err(|source| Error::grammar_syntax(Some(path.to_path_buf()), source))
+    }
+
+    /// The `from_text` function reads a grammar written in the text notation printed by its
+    /// `Display`, e.g. `C -> contact identifier identifier number number C | None`.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the grammar in the text notation.
+    ///
+    /// Returns:
+    ///
+    /// an instance of the type `Self`, or an error if the text is not a grammar.
+    pub fn from_text(text: &str) -> Result<Self, Error> {
+        notation::parse(text, None)
+    }
+
+    /// The `to_json` function serializes the grammar into its JSON representation.
+    ///
+    /// Returns:
+    ///
+    /// The grammar as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        // the grammar is always serializable, it only contains strings
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// The `to_dot` function draws the grammar as a Graphviz graph: each grammar set is a state, and
+    /// each rule is an edge labelled with its terminals to the set it continues with, or to the final
+    /// state if it ends there.
+    ///
+    /// Returns:
+    ///
+    /// The graph in the DOT language.
+    pub fn to_dot(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut dot = String::from("digraph grammar {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=circle];\n");
+        dot.push_str("    \"$accept\" [shape=doublecircle, label=\"\"];\n");
+
+        for set in &self.sets {
+            for rule in &set.rules {
+                let label = match rule.terminals.is_empty() {
+                    true => "ε".to_string(),
+                    false => rule
+                        .terminals
+                        .iter()
+                        .map(|terminal| lowercase(terminal))
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                };
+                let target = rule.non_terminal.as_deref().unwrap_or("$accept");
+
+                dot.push_str(
+                    format!(
+                        "    {} -> {} [label={}];\n",
+                        quote(&set.name),
+                        quote(target),
+                        quote(&label)
+                    )
+                    .as_str(),
+                );
+            }
+        }
+
+        dot.push('}');
+        dot
+    }
+
+    /// The `fingerprint` function returns a hash of the grammar which identifies it in reports. It
+    /// only depends on the sets, rules and synchronization tokens, not on the layout of the file.
+    ///
+    /// Returns:
+    ///
+    /// The 64-bit FNV-1a hash of the grammar, as 16 hexadecimal digits.
+    pub fn fingerprint(&self) -> String {
+        // the grammar is always serializable, it only contains strings
+        let grammar = serde_json::to_string(self).unwrap();
+
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in grammar.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        format!("{:016x}", hash)
+    }
+
+    /// The `compile` function converts the grammar into a `CompiledGrammar` ready to analyze inputs.
+    ///
+    /// Returns:
+    ///
+    /// a `CompiledGrammar` object, or an error if the grammar is not valid.
+    pub fn compile(self) -> Result<CompiledGrammar, Error> {
+        let store = self.to_store()?;
+        Ok(CompiledGrammar {
+            grammar: self,
+            store,
+        })
     }
 
     /// The `to_store` function converts a given grammar into a store by adding all terminal rule sets
-    /// and non-terminal rule sets to the store.
+    /// and non-terminal rule sets to the store. The terminals are converted into tokens of type `T`,
+    /// usually the `Token` of the crate.
     ///
     /// Returns:
     ///
-    /// a `Store` object.
-    pub fn to_store(&self) -> Store {
+    /// a `Store` object, or an error if the grammar uses an unknown terminal or set, or defines a set
+    /// twice.
+    pub fn to_store<T: Terminal>(&self) -> Result<Store<T>, Error> {
         let mut store = Store::new();
 
+        // add the synchronization tokens to store
+        for terminal in &self.sync {
+            let token = T::from_name(terminal).ok_or_else(|| Error::Grammar {
+                set: None,
+                rule: None,
+                kind: GrammarErrorKind::UnknownTerminal(terminal.clone()),
+            })?;
+            store.sync.push(token);
+        }
+
         // add all terminal rule sets to store
         for set in &self.sets {
-            let rule_set = set.to_rule_set_without_non_terminal();
+            if store.get_rule_set(set.name.as_str()).is_some() {
+                return Err(Error::Grammar {
+                    set: Some(set.name.clone()),
+                    rule: None,
+                    kind: GrammarErrorKind::DuplicateSet,
+                });
+            }
+
+            let rule_set = set.to_rule_set_without_non_terminal()?;
             store.add_rule_set(set.name.clone(), rule_set);
         }
 
@@ -54,19 +204,23 @@ impl Grammar {
 
             // get the rule set from the store
             let store_rule_set = store.get_rule_set(set.name.as_str()).unwrap();
-            let mut index = 0;
 
             // iterate over all rule sets
-            for rule_set in grammar_rule_sets {
+            for (index, rule_set) in grammar_rule_sets.into_iter().enumerate() {
                 // if the rule does not have a non-terminal, skip it
-                if rule_set.non_terminal.is_none() {
+                let Some(non_terminal) = rule_set.non_terminal else {
                     continue;
-                }
+                };
 
-                // get non-terminal name
-                let non_terminal = rule_set.non_terminal.unwrap();
                 // get rule set from store
-                let rule_set = store.get_rule_set(non_terminal.as_str()).unwrap();
+                let rule_set =
+                    store
+                        .get_rule_set(non_terminal.as_str())
+                        .ok_or_else(|| Error::Grammar {
+                            set: Some(set.name.clone()),
+                            rule: Some(index),
+                            kind: GrammarErrorKind::UnknownSet(non_terminal.clone()),
+                        })?;
 
                 // add non-terminal rule set to store
                 let store_rule_set = store_rule_set.lock().unwrap();
@@ -78,16 +232,33 @@ impl Grammar {
                     .append(
                         vec![RuleStep {
                             token: None,
+                            constraint: None,
+                            predicate: None,
                             next: Some(rule_set),
                         }]
                         .as_mut(),
                     );
-
-                index += 1;
             }
         }
 
-        store
+        Ok(store)
+    }
+}
+
+impl FromStr for Grammar {
+    type Err = Error;
+
+    /// The `from_str` function deserializes a grammar from its JSON representation.
+    ///
+    /// Arguments:
+    ///
+    /// * `s`: The `s` parameter is the JSON representation of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// an instance of the type `Self`, or an error if the text is not a grammar.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(|source| Error::grammar_syntax(None, source))
     }
 }
 
@@ -100,10 +271,110 @@ impl fmt::Display for Grammar {
             result.push('\n');
         }
 
+        if !self.sync.is_empty() {
+            result.push_str(format!("sync: {}\n", self.sync.join(" ").to_lowercase()).as_str());
+        }
+
         write!(f, "{}", result)
     }
 }
 
+/// The `CompiledGrammar` struct represents a grammar together with the `Store` of its rules, ready to
+/// analyze inputs.
+///
+/// Properties:
+///
+/// * `grammar`: The `grammar` property is the compiled `Grammar`.
+/// * `store`: The `store` property is the `Store` containing the rules of the grammar.
+#[derive(Debug, Clone)]
+pub struct CompiledGrammar {
+    grammar: Grammar,
+    store: Store,
+}
+
+impl CompiledGrammar {
+    /// The function `grammar` returns the compiled `Grammar`.
+    pub fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    /// The function `store` returns the `Store` containing the rules of the grammar.
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// The function `parser` creates a new `Parser` analyzing the given input.
+    ///
+    /// Arguments:
+    ///
+    /// * `input`: The `input` parameter is the input to analyze.
+    ///
+    /// Returns:
+    ///
+    /// a `Parser` over the input.
+    pub fn parser<'a>(&self, input: &'a str) -> Parser<'a> {
+        Parser::new(self.store.clone(), Token::lexer(input))
+    }
+
+    /// The function `validator` creates a new `Validator` for an input given in chunks.
+    ///
+    /// Returns:
+    ///
+    /// a `Validator` accepting the sentences of the grammar.
+    pub fn validator(&self) -> Validator {
+        Validator::new(&self.store)
+    }
+
+    /// The function `extract` parses the input and extracts the records of the rules which matched
+    /// it, see `capture::records`.
+    ///
+    /// Arguments:
+    ///
+    /// * `input`: The `input` parameter is the input to analyze.
+    ///
+    /// Returns:
+    ///
+    /// The records of the input, or `None` if the input is not accepted by the grammar.
+    pub fn extract(&self, input: &str) -> Option<Vec<Record>> {
+        let mut parser = self.parser(input);
+
+        match parser.parse() {
+            true => Some(capture::records(&self.grammar, parser.derivation())),
+            false => None,
+        }
+    }
+
+    /// The function `translate` parses the input and writes the output of the rules which matched
+    /// it, see `template::translate`.
+    ///
+    /// Arguments:
+    ///
+    /// * `input`: The `input` parameter is the input to translate.
+    ///
+    /// Returns:
+    ///
+    /// The output of each matched rule with a template, `None` if the input is not accepted by the
+    /// grammar, or an error if an attribute read by a template cannot be computed.
+    pub fn translate(&self, input: &str) -> Result<Option<Vec<String>>, Error> {
+        let mut parser = self.parser(input);
+
+        match parser.parse() {
+            true => template::translate(&self.grammar, parser.derivation()).map(Some),
+            false => Ok(None),
+        }
+    }
+
+    /// The function `monitor` creates a new `Monitor` for a sequence of events checked as it is
+    /// produced.
+    ///
+    /// Returns:
+    ///
+    /// a `Monitor` accepting the sequences of tokens of the grammar.
+    pub fn monitor(&self) -> Monitor {
+        Monitor::new(&self.store)
+    }
+}
+
 /// The `GrammarSet` struct represents a set of grammar rules, with a name and a vector of `GrammarRule`
 /// objects.
 ///
@@ -120,14 +391,20 @@ pub struct GrammarSet {
 impl GrammarSet {
     /// The function `to_rule_set_without_non_terminal` converts a rule set by removing non-terminal
     /// symbols.
-    pub fn to_rule_set_without_non_terminal(&self) -> crate::rules::RuleSet {
+    ///
+    /// Returns:
+    ///
+    /// a `RuleSet`, or an error if a terminal is not a token.
+    pub fn to_rule_set_without_non_terminal<T: Terminal>(
+        &self,
+    ) -> Result<crate::rules::RuleSet<T>, Error> {
         let mut rules = Vec::new();
 
-        for rule in &self.rules {
-            rules.push(rule.to_rule_without_non_terminal());
+        for (index, rule) in self.rules.iter().enumerate() {
+            rules.push(rule.to_rule_without_non_terminal(self.name.as_str(), index)?);
         }
 
-        crate::rules::RuleSet { rules }
+        Ok(crate::rules::RuleSet::new(self.name.clone(), rules))
     }
 }
 
@@ -137,7 +414,7 @@ impl fmt::Display for GrammarSet {
     /// Arguments:
     ///
     /// * `f`: A mutable reference to a `fmt::Formatter` object. This object is used for formatting and
-    /// writing output.
+    ///   writing output.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut result: Vec<String> = vec![];
 
@@ -154,33 +431,137 @@ impl fmt::Display for GrammarSet {
 /// Properties:
 ///
 /// * `terminals`: A vector of strings representing the terminal symbols in the grammar rule. Terminal
-/// symbols are symbols that cannot be further expanded or derived in the grammar.
+///   symbols are symbols that cannot be further expanded or derived in the grammar. A terminal may be
+///   followed by a constraint on its text between brackets, e.g. `Number[0..100]` (see `Constraint`),
+///   and preceded by the name capturing its text, e.g. `from:Identifier` (see `capture::records`).
 /// * `non_terminal`: The `non_terminal` property in the `GrammarRule` struct represents a non-terminal
-/// symbol in a grammar rule. In formal language theory, a non-terminal symbol is a symbol that can be
-/// replaced by a sequence of other symbols according to the rules of a grammar. Non-terminal symbols
-/// are typically represented by
+///   symbol in a grammar rule. In formal language theory, a non-terminal symbol is a symbol that can be
+///   replaced by a sequence of other symbols according to the rules of a grammar. Non-terminal symbols
+///   are typically represented by
+/// * `predicates`: The conditions on the captures of the rule, each written `name: condition`, e.g.
+///   `ordered: start < end`, the rule only matching when they all hold (see `Predicate`).
+/// * `symbols`: The identifiers the rule declares or uses once the input is accepted, e.g.
+///   `declare node: name` or `use node: from, to`, and `scope` to open a new scope (see `Symbol`).
+/// * `attributes`: The attributes computed when the rule matches, each written `name = expression`,
+///   e.g. `count = 1 + next.count` (see `Attribute`).
+/// * `template`: The output of the rule when the input is translated, where `${name}` is replaced by
+///   a capture or an attribute of the rule (see `Template`).
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GrammarRule {
-    terminals: Vec<String>,
-    non_terminal: Option<String>,
+    pub terminals: Vec<String>,
+    pub non_terminal: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub predicates: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
 }
 
 impl GrammarRule {
     /// The function `to_rule_without_non_terminal` converts a list of terminals into a rule without
     /// non-terminals in Rust.
-    pub fn to_rule_without_non_terminal(&self) -> crate::rules::Rule {
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: The `name` parameter is the name of the grammar set the rule belongs to.
+    /// * `index`: The `index` parameter is the index of the rule in its grammar set.
+    ///
+    /// Returns:
+    ///
+    /// a `Rule`, or an error if a terminal is not a token or has an invalid constraint, or if a
+    /// predicate, a symbol entry, an attribute or the template is not valid.
+    pub fn to_rule_without_non_terminal<T: Terminal>(
+        &self,
+        name: &str,
+        index: usize,
+    ) -> Result<crate::rules::Rule<T>, Error> {
+        // the attributes, the symbols and the template are only used once the input is accepted,
+        // but checked beforehand
+        Attribute::parse_all(self).map_err(|(attribute, message)| Error::Grammar {
+            set: Some(name.to_string()),
+            rule: Some(index),
+            kind: GrammarErrorKind::InvalidAttribute { attribute, message },
+        })?;
+        Symbol::parse_all(self).map_err(|(symbol, message)| Error::Grammar {
+            set: Some(name.to_string()),
+            rule: Some(index),
+            kind: GrammarErrorKind::InvalidSymbol { symbol, message },
+        })?;
+        Template::compile(self).map_err(|message| Error::Grammar {
+            set: Some(name.to_string()),
+            rule: Some(index),
+            kind: GrammarErrorKind::InvalidTemplate {
+                template: self.template.clone().unwrap_or_default(),
+                message,
+            },
+        })?;
+
         let mut steps = Vec::new();
+        let mut captures = Vec::new();
 
         for terminal in &self.terminals {
+            let error = |kind| Error::Grammar {
+                set: Some(name.to_string()),
+                rule: Some(index),
+                kind,
+            };
+
+            // the same name cannot capture two terminals of a rule
+            let (capture, captured) = capture::split(terminal);
+            if let Some(capture) = capture {
+                if captures.contains(&capture) {
+                    return Err(error(GrammarErrorKind::DuplicateCapture(
+                        capture.to_string(),
+                    )));
+                }
+                captures.push(capture);
+            }
+
+            let (terminal_name, constraint) = constraint::split(captured).map_err(|message| {
+                error(GrammarErrorKind::InvalidConstraint {
+                    terminal: terminal.clone(),
+                    message,
+                })
+            })?;
+            let token = T::from_name(terminal_name)
+                .ok_or_else(|| error(GrammarErrorKind::UnknownTerminal(terminal.clone())))?;
+
             steps.push(crate::rules::RuleStep {
-                token: Some(Token::from_string(terminal)),
+                token: Some(token),
+                constraint,
+                predicate: None,
                 next: None,
             });
         }
 
-        crate::rules::Rule {
-            steps: Arc::new(Mutex::new(steps)),
+        // the predicates are checked once the terminals are matched, before the next rule set
+        for text in &self.predicates {
+            let predicate =
+                Predicate::compile(text, self, name, index).map_err(|message| Error::Grammar {
+                    set: Some(name.to_string()),
+                    rule: Some(index),
+                    kind: GrammarErrorKind::InvalidPredicate {
+                        predicate: text.clone(),
+                        message,
+                    },
+                })?;
+
+            steps.push(crate::rules::RuleStep {
+                token: None,
+                constraint: None,
+                predicate: Some(predicate),
+                next: None,
+            });
         }
+
+        Ok(crate::rules::Rule {
+            name: name.to_string(),
+            index,
+            steps: Arc::new(Mutex::new(steps)),
+        })
     }
 }
 
@@ -189,17 +570,103 @@ impl fmt::Display for GrammarRule {
         let mut result: Vec<String> = vec![];
 
         for terminal in &self.terminals {
-            result.push(terminal.to_string().to_lowercase());
+            result.push(lowercase(terminal));
         }
 
-        for non_terminal in &self.non_terminal {
-            result.push(non_terminal.to_string().to_uppercase());
+        if let Some(non_terminal) = &self.non_terminal {
+            result.push(non_terminal.to_string());
         }
 
         if result.is_empty() {
             result.push("None".to_string());
         }
 
+        // the predicates, the symbols, the attributes and the template share the braces at the end
+        // of the rule
+        let block: Vec<String> = self
+            .predicates
+            .iter()
+            .map(|predicate| format!("require {}", predicate))
+            .chain(self.symbols.iter().cloned())
+            .chain(self.attributes.iter().cloned())
+            .chain(
+                self.template
+                    .iter()
+                    .map(|text| format!("emit {}", template::quote(text))),
+            )
+            .collect();
+        if !block.is_empty() {
+            result.push(format!("{{ {} }}", block.join("; ")));
+        }
+
         write!(f, "{}", result.join(" "))
     }
 }
+
+/// The function `lowercase` writes a terminal in lowercase as in the text notation, e.g.
+/// `end:number[..100]`: only its name is lowercased, the capture and the constraint are kept as
+/// written.
+fn lowercase(terminal: &str) -> String {
+    let (capture, terminal) = capture::split(terminal);
+    let name = constraint::name(terminal);
+    let terminal = format!("{}{}", name.to_lowercase(), &terminal[name.len()..]);
+
+    match capture {
+        Some(capture) => format!("{}:{}", capture, terminal),
+        None => terminal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_the_non_terminal_to_its_own_rule() {
+        // the first rule of `C` has no non-terminal, the second one ends with `C`
+        let grammar: Grammar = serde_json::from_str(
+            r#"{"sets": [{"name": "C", "rules": [
+                {"terminals": ["Identifier"], "non_terminal": null},
+                {"terminals": ["Contact"], "non_terminal": "C"}
+            ]}]}"#,
+        )
+        .unwrap();
+
+        let store: Store = grammar.to_store().unwrap();
+        let set = store.get_rule_set("C").unwrap();
+        let set = set.lock().unwrap();
+        let next = |index: usize| {
+            let steps = set.rules[index].steps.lock().unwrap();
+            steps.last().unwrap().next.is_some()
+        };
+
+        assert!(!next(0));
+        assert!(next(1));
+    }
+
+    #[test]
+    fn starts_the_analysis_at_the_first_set() {
+        let grammar = Grammar::from_text("S -> C\nC -> contact identifier C | None\nR -> rate")
+            .unwrap()
+            .compile()
+            .unwrap();
+
+        // the derivation begins at `S` every time, never at `C`
+        for _ in 0..16 {
+            let mut parser = grammar.parser("contact A\n");
+            assert!(parser.parse());
+            let names: Vec<&str> = parser
+                .derivation()
+                .iter()
+                .map(|derivation| derivation.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["S", "C", "C"]);
+        }
+
+        // an input only matching a later set is not accepted
+        assert!(!grammar.parser("rate").parse());
+        let mut validator = grammar.validator();
+        assert!(validator.feed("rate").is_err() || validator.finish().is_err());
+        assert!(grammar.monitor().step(Token::Rate).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+use logos::Logos;
+
+use crate::{
+    parser::Token,
+    terminal::{Terminal, Tokens},
+};
+
+/// The `Lexeme` struct represents a token read by the lexer, with the text it was read from.
+///
+/// Properties:
+///
+/// * `token`: The kind of the token, or `None` if the text is not a token.
+/// * `text`: The text of the input matched by the token.
+/// * `span`: The byte span of the text in the input.
+/// * `line`: The line of the start of the text, starting at 1.
+/// * `column`: The column of the start of the text, in characters, starting at 1.
+/// * `value`: The value of the token for the numbers (see `Terminal::value`), `None` otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexeme<T = Token> {
+    pub token: Option<T>,
+    pub text: String,
+    pub span: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub value: Option<u64>,
+}
+
+impl<T: Terminal> Lexeme<T> {
+    /// The function `new` creates a new instance of the `Lexeme` struct, computing the value of the
+    /// token from its text.
+    ///
+    /// Arguments:
+    ///
+    /// * `token`: The `token` parameter is the kind of the token, `None` for an invalid token.
+    /// * `text`: The `text` parameter is the text matched by the token.
+    /// * `span`: The `span` parameter is the byte span of the text in the input.
+    /// * `line`: The `line` parameter is the line of the start of the text.
+    /// * `column`: The `column` parameter is the column of the start of the text.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Lexeme` struct is being returned.
+    pub fn new(
+        token: Option<T>,
+        text: String,
+        span: Range<usize>,
+        line: usize,
+        column: usize,
+    ) -> Lexeme<T> {
+        let value = token.as_ref().and_then(|token| token.value(&text));
+
+        Lexeme {
+            token,
+            text,
+            span,
+            line,
+            column,
+            value,
+        }
+    }
+}
+
+/// The function `lex` runs the lexer alone over the input, without grammar.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the text to read.
+///
+/// Returns:
+///
+/// The lexemes of the input, in order, including the text which is not a token.
+pub fn lex(input: &str) -> Vec<Lexeme> {
+    Tokens::new(Token::lexer(input)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report;
+
+    #[test]
+    fn locates_the_lexemes_across_lines() {
+        let lexemes = lex("contact A B\n\n  rate 1\n$ delay");
+
+        let located: Vec<(Option<Token>, &str, usize, usize)> = lexemes
+            .iter()
+            .map(|l| (l.token.clone(), l.text.as_str(), l.line, l.column))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                (Some(Token::Contact), "contact", 1, 1),
+                (Some(Token::Identifier), "A", 1, 9),
+                (Some(Token::Identifier), "B", 1, 11),
+                (Some(Token::Rate), "rate", 3, 3),
+                (Some(Token::Number), "1", 3, 8),
+                (None, "$", 4, 1),
+                (Some(Token::Delay), "delay", 4, 3),
+            ]
+        );
+        assert_eq!(lexemes[3].span, 15..19);
+        assert_eq!(lexemes[5].span, 22..23);
+    }
+
+    #[test]
+    fn counts_the_columns_in_characters() {
+        let lexemes = lex("contact é\nü rate ö 1");
+
+        let located: Vec<(usize, usize, usize)> = lexemes
+            .iter()
+            .map(|l| (l.span.start, l.line, l.column))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                (0, 1, 1),
+                (8, 1, 9),
+                (11, 2, 1),
+                (14, 2, 3),
+                (19, 2, 8),
+                (22, 2, 10)
+            ]
+        );
+        assert_eq!(report::location("contact é\nü rate ö 1", 22), (2, 10));
+    }
+
+    #[test]
+    fn lexes_an_empty_input() {
+        assert!(lex("").is_empty());
+        assert!(lex(" \n\n").is_empty());
+    }
+
+    #[test]
+    fn reads_the_value_of_numbers() {
+        let lexemes = lex("20 A 18446744073709551615 18446744073709551616");
+
+        let values: Vec<Option<u64>> = lexemes.iter().map(|lexeme| lexeme.value).collect();
+        assert_eq!(values, vec![Some(20), None, Some(u64::MAX), None]);
+
+        // a number past 64 bits has no value but keeps its text
+        assert_eq!(lexemes[3].token, Some(Token::Number));
+        assert_eq!(lexemes[3].text, "18446744073709551616");
+    }
+
+    #[test]
+    fn computes_the_value_of_a_new_lexeme() {
+        let lexeme = Lexeme::new(Some(Token::Number), "42".to_string(), 3..5, 2, 1);
+        assert_eq!(lexeme.value, Some(42));
+
+        let lexeme: Lexeme = Lexeme::new(None, "42".to_string(), 3..5, 2, 1);
+        assert_eq!(lexeme.value, None);
+    }
+}
@@ -0,0 +1,39 @@
+//! Validation of inputs against right-linear grammars described in JSON.
+//!
+//! A `Grammar` is read with `Grammar::from_path`, `Grammar::from_str` or `Grammar::from_text`, or
+//! built with a `GrammarBuilder`, compiled once into a `CompiledGrammar`, and inputs are then
+//! checked with `validate`, or given in chunks to a `Validator`. Sequences of events produced at
+//! runtime are checked one event at a time by a `Monitor`, and random inputs are produced by a
+//! `Generator`.
+
+pub mod attribute;
+pub mod builder;
+pub mod capture;
+pub mod constraint;
+pub mod debugger;
+pub mod error;
+pub mod expression;
+pub mod generator;
+pub mod grammar;
+pub mod lexer;
+pub mod monitor;
+pub mod notation;
+pub mod output;
+pub mod parser;
+pub mod plan;
+pub mod predicate;
+pub mod records;
+pub mod report;
+pub mod rules;
+pub mod store;
+pub mod symbol;
+pub mod template;
+pub mod terminal;
+pub mod validator;
+
+pub use builder::GrammarBuilder;
+pub use error::{Error, GrammarErrorKind};
+pub use grammar::{CompiledGrammar, Grammar};
+pub use monitor::{Monitor, Violation};
+pub use report::{validate, Report};
+pub use validator::Validator;
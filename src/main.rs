@@ -1,51 +1,917 @@
-use clap::Parser;
-use logos::Logos;
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use crate::parser::Token;
-
-pub mod grammar;
-pub mod parser;
-pub mod rules;
-pub mod store;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use rust_automate::{
+    attribute::Attributes,
+    capture,
+    debugger::{Breakpoint, Debugger},
+    generator::Generator,
+    lexer,
+    output::{self, GrammarOutput, Output, Status, Timing},
+    parser::Tracer,
+    plan::{self, Finding},
+    records::{self, RecordReport},
+    report::{location, validate_with_tracer},
+    template, CompiledGrammar, Error, Grammar, GrammarErrorKind,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args {
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    // without subcommand, the inputs are validated
+    #[command(flatten)]
+    validate: Option<ValidateArgs>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ValidateArgs {
     /// The path of the grammar to use
     #[arg(short, long)]
     grammar: String,
 
-    /// The path of the input file to validate
+    /// The path of the input file to validate, repeated to validate several files
+    #[arg(short, long, required = true)]
+    input: Vec<String>,
+
+    /// The name of the rule set each line of the input must match on its own
+    #[arg(short, long)]
+    records: Option<String>,
+
+    /// Also validate the sequence of the records against the whole grammar
+    #[arg(short, long, requires = "records")]
+    structure: bool,
+
+    /// Report every error of the input instead of the first one
+    #[arg(short, long)]
+    all_errors: bool,
+
+    /// Print each step of the analysis on the standard error
     #[arg(short, long)]
-    input: String,
+    trace: bool,
+
+    /// Also check the consistency of the input as a contact plan, e.g. overlapping contacts
+    #[arg(short, long)]
+    plan: bool,
+
+    /// The format of the result printed on the standard output
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// The grammar, the input and the result, for humans
+    Text,
+    /// Only a JSON report of the result, for scripts
+    Json,
+    /// Only a SARIF log of the errors, for code scanning
+    Sarif,
+    /// Only a JUnit XML report with a test case per input, for CI dashboards
+    Junit,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Notation {
+    /// The JSON format of the grammar files
+    Json,
+    /// The text notation printed by `show`
+    Text,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum DataFormat {
+    /// A JSON array with an object per record
+    Json,
+    /// A CSV table with a row per record
+    Csv,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Validate input files with a grammar (the default without subcommand)
+    Validate(ValidateArgs),
+
+    /// Check that a grammar is valid without validating any input
+    Check {
+        /// The path of the grammar to check
+        #[arg(short, long)]
+        grammar: String,
+    },
+
+    /// Print a grammar in the text notation
+    Show {
+        /// The path of the grammar to print
+        #[arg(short, long)]
+        grammar: String,
+
+        /// Only print the grammar set with this name
+        #[arg(short, long)]
+        set: Option<String>,
+    },
+
+    /// Print the tokens of an input, without grammar
+    Tokens {
+        /// The path of the input file to read
+        #[arg(short, long)]
+        input: String,
+    },
+
+    /// Convert a grammar between the JSON format and the text notation
+    Convert {
+        /// The path of the grammar to convert, in either format
+        #[arg(short, long)]
+        grammar: String,
+
+        /// The format to convert the grammar to
+        #[arg(short, long, value_enum)]
+        to: Notation,
+    },
+
+    /// Print a grammar as a Graphviz graph
+    Dot {
+        /// The path of the grammar to draw
+        #[arg(short, long)]
+        grammar: String,
+    },
+
+    /// Generate random inputs accepted by a grammar
+    Generate {
+        /// The path of the grammar to use
+        #[arg(short, long)]
+        grammar: String,
+
+        /// The name of the grammar set the inputs must match, the first one if not given
+        #[arg(short, long)]
+        start: Option<String>,
+
+        /// The number of inputs to generate
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+
+        /// The number of rules after which an input heads to its end
+        #[arg(short, long, default_value_t = 10)]
+        length: usize,
+
+        /// The seed of the random choices, to generate the same inputs again
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Extract the captured terminals of an input as records
+    Extract {
+        /// The path of the grammar to use, with captures such as `from:Identifier`
+        #[arg(short, long)]
+        grammar: String,
+
+        /// The path of the input file to read
+        #[arg(short, long)]
+        input: String,
+
+        /// The format of the records printed on the standard output
+        #[arg(short, long, value_enum, default_value_t = DataFormat::Json)]
+        format: DataFormat,
+    },
+
+    /// Translate an input with the output templates of the rules of a grammar
+    Translate {
+        /// The path of the grammar to use, with templates such as `emit "${from} -> ${to}"`
+        #[arg(short, long)]
+        grammar: String,
+
+        /// The path of the input file to translate
+        #[arg(short, long)]
+        input: String,
+    },
+
+    /// Step through the analysis of an input interactively
+    Debug {
+        /// The path of the grammar to use
+        #[arg(short, long)]
+        grammar: String,
+
+        /// The path of the input file to analyze
+        #[arg(short, long)]
+        input: String,
+
+        /// Stop when a rule of the grammar set with this name is tried, or when a token of this
+        /// line is matched
+        #[arg(short, long = "break")]
+        breakpoints: Vec<String>,
+    },
+}
+
+/// The input is accepted by the grammar.
+const EXIT_ACCEPTED: i32 = 0;
+/// The input is not accepted by the grammar.
+const EXIT_REJECTED: i32 = 1;
+// 2 is the exit code of clap when the arguments are not valid
+/// The input contains an invalid token.
+const EXIT_LEX: i32 = 3;
+/// The grammar is not valid.
+const EXIT_GRAMMAR: i32 = 4;
+/// A file cannot be read.
+const EXIT_IO: i32 = 5;
+
 fn main() {
-    // parse the arguments
-    let args = Args::parse();
+    // parse the arguments, validating the inputs without subcommand
+    let cli = Cli::parse();
+    let command = match (cli.command, cli.validate) {
+        (Some(command), _) => command,
+        (None, Some(args)) => Command::Validate(args),
+        (None, None) => Cli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "a subcommand or the inputs to validate are required",
+            )
+            .exit(),
+    };
+
+    let result = match &command {
+        Command::Validate(args) => validate(args),
+        Command::Check { grammar } => check(grammar),
+        Command::Show { grammar, set } => show(grammar, set.as_deref()),
+        Command::Tokens { input } => tokens(input),
+        Command::Convert { grammar, to } => convert(grammar, *to),
+        Command::Dot { grammar } => dot(grammar),
+        Command::Generate {
+            grammar,
+            start,
+            count,
+            length,
+            seed,
+        } => generate(grammar, start.as_deref(), *count, *length, *seed),
+        Command::Extract {
+            grammar,
+            input,
+            format,
+        } => extract(grammar, input, *format),
+        Command::Translate { grammar, input } => translate(grammar, input),
+        Command::Debug {
+            grammar,
+            input,
+            breakpoints,
+        } => debug(grammar, input, breakpoints),
+    };
+
+    let error = match result {
+        Ok(code) => std::process::exit(code),
+        Err(error) => error,
+    };
+
+    match &command {
+        Command::Validate(args) if args.format != Format::Text => {
+            // every input is reported as not validated
+            let outputs: Vec<Output> = args
+                .input
+                .iter()
+                .map(|path| {
+                    let mut output = Output::from_error(&error);
+                    output.grammar.path = Some(args.grammar.clone());
+                    output.input = Some(path.clone());
+                    output
+                })
+                .collect();
+            print_outputs(args.format, &outputs);
+        }
+        _ => eprintln!("🚫 {}", error),
+    }
+
+    std::process::exit(exit_code(&Output::from_error(&error)));
+}
+
+/// The function `exit_code` returns the exit code of the process for the result of an input.
+///
+/// Arguments:
+///
+/// * `output`: The `output` parameter is the result of the validation of the input.
+///
+/// Returns:
+///
+/// The exit code matching the status of the output and the kind of its errors.
+fn exit_code(output: &Output) -> i32 {
+    let kinds = || output.errors.iter().map(|error| error.kind.as_str());
+
+    match output.status {
+        Status::Accepted => EXIT_ACCEPTED,
+        Status::Rejected if kinds().any(|kind| kind == "lex") => EXIT_LEX,
+        Status::Rejected => EXIT_REJECTED,
+        Status::Error if kinds().any(|kind| kind == "io") => EXIT_IO,
+        Status::Error => EXIT_GRAMMAR,
+    }
+}
+
+/// The function `validate` validates the input files with the grammar file given as arguments and
+/// prints the result in the requested format.
+///
+/// Arguments:
+///
+/// * `args`: The `args` parameter is the arguments of the `validate` subcommand.
+///
+/// Returns:
+///
+/// The exit code of the process once the result is printed, the highest one among the inputs, or
+/// an error if the grammar cannot be read or is not valid.
+fn validate(args: &ValidateArgs) -> Result<i32, Error> {
+    let grammar_path = args.grammar.clone();
+    let text = args.format == Format::Text;
 
     // parse the grammar at the given path
-    let grammar = grammar::Grammar::parse(args.grammar.as_str());
-    println!("📚 Grammar to use:\n");
-    println!("{}", grammar);
+    let start = Instant::now();
+    let grammar = Grammar::from_path(grammar_path.as_str())?;
+
+    // the record rule set is an argument, an unknown one is a usage error
+    if let Some(record) = &args.records {
+        if !grammar.sets.iter().any(|set| &set.name == record) {
+            Cli::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("the grammar has no set `{}` for '--records'", record),
+                )
+                .exit();
+        }
+    }
+
+    if text {
+        println!("📚 Grammar to use:\n");
+        println!("{}", grammar);
+    }
 
-    // create a new store with the rules
-    let store = grammar.to_store();
+    // compile the grammar into a store of rules
+    let fingerprint = grammar.fingerprint();
+    let grammar = grammar.compile()?;
+    let compile = milliseconds(start.elapsed());
+
+    let mut outputs = Vec::new();
+    for input_path in &args.input {
+        let start = Instant::now();
+        let mut output = match validate_input(args, &grammar, input_path) {
+            Ok(output) => output,
+            Err(error) => {
+                // the other inputs are still validated
+                if text {
+                    eprintln!("🚫 {}", error);
+                }
+                Output::from_error(&error)
+            }
+        };
+
+        output.grammar = GrammarOutput {
+            path: Some(grammar_path.clone()),
+            fingerprint: Some(fingerprint.clone()),
+        };
+        output.input = Some(input_path.clone());
+        output.timing = Some(Timing {
+            compile_ms: compile,
+            validate_ms: milliseconds(start.elapsed()),
+        });
+        outputs.push(output);
+    }
+
+    if !text {
+        print_outputs(args.format, &outputs);
+    }
+
+    Ok(outputs.iter().map(exit_code).max().unwrap_or(EXIT_ACCEPTED))
+}
+
+/// The function `validate_input` validates an input file with the compiled grammar, printing the
+/// input and the result in the text format.
+///
+/// Arguments:
+///
+/// * `args`: The `args` parameter is the arguments of the `validate` subcommand.
+/// * `grammar`: The `grammar` parameter is the grammar to validate the input with.
+/// * `input_path`: The `input_path` parameter is the path of the input file.
+///
+/// Returns:
+///
+/// The `Output` of the validation, or an error if the input cannot be read or the record rule set
+/// does not exist.
+fn validate_input(
+    args: &ValidateArgs,
+    grammar: &CompiledGrammar,
+    input_path: &str,
+) -> Result<Output, Error> {
+    let text = args.format == Format::Text;
 
     // read the input file and create the lexer
-    let input: String = std::fs::read_to_string(args.input.as_str()).unwrap();
-    println!("📝 Input to be analyzed:\n");
-    println!("{}", input);
-    let lexer = Token::lexer(input.as_str());
+    let input = read_input(input_path)?;
+    if text {
+        println!("📝 Input to be analyzed:\n");
+        println!("{}", input);
+    }
 
-    // create a new parser with the store
-    let mut parser = crate::parser::Parser::new(store, lexer);
+    let mut output = match &args.records {
+        // validate the input line by line if requested
+        Some(record) => {
+            let report =
+                records::validate_records(grammar.store(), record, &input, args.structure)?;
+            if text {
+                print_records(record, &report);
+            }
+            Output::from_records(&report, &input)
+        }
+        None => {
+            // validate the input, printing each step of the analysis if requested
+            let tracer: Option<Tracer> = match args.trace {
+                true => Some(Box::new(|event| eprintln!("{}", event))),
+                false => None,
+            };
+            let report = validate_with_tracer(grammar, input.as_str(), tracer)?;
+            if text {
+                print_report(&input, &report.errors, report.accepted, args.all_errors);
+                print_attributes(&input, &report.attributes);
+            }
+            Output::from_report(&report, &input)
+        }
+    };
 
-    // parse the input
-    let correct = parser.parse();
+    // the findings of the contact plan do not change the acceptance of the input
+    if args.plan {
+        let findings = plan::check(&input);
+        if text {
+            print_findings(&findings);
+        }
+        output.findings = Some(findings);
+    }
+
+    Ok(output)
+}
 
-    match correct {
+/// The function `print_outputs` prints the outputs of the inputs in a machine-readable format.
+///
+/// Arguments:
+///
+/// * `format`: The `format` parameter is the format to print, other than the text.
+/// * `outputs`: The `outputs` parameter is the output of each input.
+fn print_outputs(format: Format, outputs: &[Output]) {
+    match format {
+        Format::Text => {}
+        Format::Json => println!("{}", output::to_json(outputs)),
+        Format::Sarif => println!("{}", output::to_sarif(outputs)),
+        Format::Junit => println!("{}", output::to_junit(outputs)),
+    }
+}
+
+/// The function `print_report` prints the result of the validation of the whole input.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the validated input, used to locate the errors.
+/// * `errors`: The `errors` parameter is the list of errors found in the input.
+/// * `accepted`: The `accepted` parameter tells whether the input is accepted.
+/// * `all_errors`: The `all_errors` parameter tells whether every error is printed, or only the
+///   first one.
+fn print_report(input: &str, errors: &[Error], accepted: bool, all_errors: bool) {
+    match accepted {
+        true => println!("✅ The input is correct"),
+        false => {
+            println!("🚫 The input is incorrect");
+
+            // only the first error is relevant unless all of them are requested
+            let count = match all_errors {
+                true => errors.len(),
+                false => 1,
+            };
+
+            for error in errors.iter().take(count) {
+                let (line, column) = location(input, error.offset());
+                match error {
+                    Error::Redeclared { previous, .. } => {
+                        let (before, at) = location(input, previous.start);
+                        println!(
+                            "   {}:{}: {}, first at {}:{}",
+                            line, column, error, before, at
+                        );
+                    }
+                    _ => println!("   {}:{}: {}", line, column, error),
+                }
+            }
+        }
+    }
+}
+
+/// The function `print_attributes` prints the attributes of the rules which matched the input, if
+/// any, each rule being located by the start of its tokens.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the validated input, used to locate the rules.
+/// * `attributes`: The `attributes` parameter is the attributes of each matched rule.
+fn print_attributes(input: &str, attributes: &[Attributes]) {
+    if attributes.is_empty() {
+        return;
+    }
+
+    println!("\n📐 Attributes:\n");
+    for rule in attributes {
+        let (line, column) = location(input, rule.span.start);
+        let values: Vec<String> = rule
+            .values
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect();
+        println!(
+            "   {}:{}: {} #{}: {}",
+            line,
+            column,
+            rule.set,
+            rule.rule,
+            values.join(", ")
+        );
+    }
+}
+
+/// The function `print_findings` prints the findings of the input read as a contact plan, each one
+/// being located by its line.
+///
+/// Arguments:
+///
+/// * `findings`: The `findings` parameter is the list of inconsistencies of the contact plan.
+fn print_findings(findings: &[Finding]) {
+    println!("\n🛰️  Contact plan:\n");
+    if findings.is_empty() {
+        println!("   no finding");
+    }
+
+    for finding in findings {
+        println!(
+            "   {}:{}: {} ({})",
+            finding.line,
+            finding.column,
+            finding.message,
+            finding.check.name()
+        );
+    }
+}
+
+/// The function `print_records` prints the result of each line of the input validated in record
+/// mode.
+///
+/// Arguments:
+///
+/// * `record`: The `record` parameter is the name of the rule set each line must match.
+/// * `report`: The `report` parameter is the result of the validation of the records.
+fn print_records(record: &str, report: &RecordReport) {
+    println!("📋 Records validated with `{}`:\n", record);
+    for line in &report.lines {
+        match &line.error {
+            None => println!("✅ line {}: {}", line.number, line.text),
+            Some(error) => println!("🚫 line {}: {} ({})", line.number, line.text, error),
+        }
+    }
+    println!();
+
+    match &report.structure {
+        Some(Ok(())) => println!("✅ The sequence of records is correct"),
+        Some(Err((line, error))) => {
+            println!("🚫 The sequence of records is incorrect at line {}", line);
+            println!("   {}", error);
+        }
+        None => {}
+    }
+
+    match report.is_correct() {
         true => println!("✅ The input is correct"),
         false => println!("🚫 The input is incorrect"),
     }
 }
+
+/// The function `milliseconds` converts a duration into a number of milliseconds.
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+/// The function `check` checks that the grammar is valid and prints a summary of it.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to check.
+///
+/// Returns:
+///
+/// The exit code of the process once the summary is printed, or an error if the grammar cannot be
+/// read or is not valid.
+fn check(grammar: &str) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?.compile()?;
+    let grammar = grammar.grammar();
+
+    let rules: usize = grammar.sets.iter().map(|set| set.rules.len()).sum();
+    println!(
+        "✅ The grammar is valid: {} sets, {} rules (fingerprint {})",
+        grammar.sets.len(),
+        rules,
+        grammar.fingerprint()
+    );
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `show` prints the grammar in the text notation.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to print.
+/// * `set`: The `set` parameter is the name of the only grammar set to print, if any.
+///
+/// Returns:
+///
+/// The exit code of the process once the grammar is printed, or an error if the grammar cannot be
+/// read or has no set with the given name.
+fn show(grammar: &str, set: Option<&str>) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?;
+
+    match set {
+        Some(name) => {
+            let set = grammar
+                .sets
+                .iter()
+                .find(|set| set.name == name)
+                .ok_or_else(|| Error::Grammar {
+                    set: None,
+                    rule: None,
+                    kind: GrammarErrorKind::UnknownSet(name.to_string()),
+                })?;
+            println!("{}", set);
+        }
+        None => print!("{}", grammar),
+    }
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `tokens` prints the tokens of the input, as read by the lexer: the location, the
+/// byte span, the kind and the text of each token, the text which is not a token being `invalid`.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the path of the input file.
+///
+/// Returns:
+///
+/// The exit code of the process once the tokens are printed, `EXIT_LEX` if the input contains an
+/// invalid token, or an error if the input cannot be read.
+fn tokens(input: &str) -> Result<i32, Error> {
+    let input = read_input(input)?;
+    let mut code = EXIT_ACCEPTED;
+
+    for lexeme in lexer::lex(input.as_str()) {
+        let kind = match &lexeme.token {
+            Some(token) => format!("{:?}", token),
+            None => {
+                code = EXIT_LEX;
+                "invalid".to_string()
+            }
+        };
+
+        println!(
+            "{:<8} {:<10} {:<12} {:?}",
+            format!("{}:{}", lexeme.line, lexeme.column),
+            format!("{}..{}", lexeme.span.start, lexeme.span.end),
+            kind,
+            lexeme.text
+        );
+    }
+
+    Ok(code)
+}
+
+/// The function `convert` prints the grammar in the given format.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to convert, in either format.
+/// * `to`: The `to` parameter is the format to convert the grammar to.
+///
+/// Returns:
+///
+/// The exit code of the process once the grammar is printed, or an error if the grammar cannot be
+/// read.
+fn convert(grammar: &str, to: Notation) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?;
+
+    match to {
+        Notation::Json => println!("{}", grammar.to_json()),
+        Notation::Text => print!("{}", grammar),
+    }
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `dot` prints the grammar as a Graphviz graph.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to draw.
+///
+/// Returns:
+///
+/// The exit code of the process once the graph is printed, or an error if the grammar cannot be
+/// read.
+fn dot(grammar: &str) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?;
+    println!("{}", grammar.to_dot());
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `generate` prints random inputs accepted by the grammar, separated by empty lines.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to use.
+/// * `start`: The `start` parameter is the name of the grammar set the inputs must match, the first
+///   set of the grammar if `None`.
+/// * `count`: The `count` parameter is the number of inputs to generate.
+/// * `length`: The `length` parameter is the number of rules after which an input heads to its end.
+/// * `seed`: The `seed` parameter is the seed of the random choices, the current time if `None`.
+///
+/// Returns:
+///
+/// The exit code of the process once the inputs are printed, or an error if the grammar is not
+/// valid or cannot produce an input.
+fn generate(
+    grammar: &str,
+    start: Option<&str>,
+    count: usize,
+    length: usize,
+    seed: Option<u64>,
+) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?.compile()?;
+    let grammar = grammar.grammar();
+
+    let Some(start) = start.or(grammar.sets.first().map(|set| set.name.as_str())) else {
+        return Ok(EXIT_ACCEPTED);
+    };
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64)
+    });
+
+    let mut generator = Generator::new(grammar, seed).with_length(length);
+    for index in 0..count {
+        if index > 0 {
+            println!();
+        }
+        println!("{}", generator.generate(start)?);
+    }
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `extract` prints the records captured by the rules which matched the input.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to use.
+/// * `input`: The `input` parameter is the path of the input file.
+/// * `format`: The `format` parameter is the format of the records.
+///
+/// Returns:
+///
+/// The exit code of the process once the records are printed, the first error of the input being
+/// printed on the standard error if it is rejected, or an error if a file cannot be read or the
+/// grammar is not valid.
+fn extract(grammar: &str, input: &str, format: DataFormat) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?.compile()?;
+    let input = read_input(input)?;
+
+    let Some(records) = grammar.extract(&input) else {
+        let report = validate_with_tracer(&grammar, &input, None)?;
+        eprintln!("🚫 The input is incorrect");
+        if let Some(error) = report.errors.first() {
+            let (line, column) = location(&input, error.offset());
+            eprintln!("   {}:{}: {}", line, column, error);
+        }
+        return Ok(exit_code(&Output::from_report(&report, &input)));
+    };
+
+    match format {
+        DataFormat::Json => println!("{}", capture::to_json(&records)),
+        DataFormat::Csv => print!("{}", capture::to_csv(&records)),
+    }
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `translate` prints the output of the rules which matched the input, a line per
+/// rule with a template.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to use.
+/// * `input`: The `input` parameter is the path of the input file to translate.
+///
+/// Returns:
+///
+/// The exit code of the process once the output is printed, the first error of the input being
+/// printed on the standard error if it is rejected, or an error if a file cannot be read or the
+/// grammar is not valid.
+fn translate(grammar: &str, input: &str) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?.compile()?;
+    let input = read_input(input)?;
+
+    // the input is only translated if it is accepted, its identifiers and attributes included, from
+    // the rules which matched it during the validation
+    let report = validate_with_tracer(&grammar, &input, None)?;
+    if !report.accepted {
+        eprintln!("🚫 The input is incorrect");
+        if let Some(error) = report.errors.first() {
+            let (line, column) = location(&input, error.offset());
+            eprintln!("   {}:{}: {}", line, column, error);
+        }
+        return Ok(exit_code(&Output::from_report(&report, &input)));
+    }
+
+    let lines = template::translate(grammar.grammar(), &report.derivation)?;
+
+    for line in lines {
+        println!("{}", line);
+    }
+
+    Ok(EXIT_ACCEPTED)
+}
+
+/// The function `debug` analyzes the input with the interactive debugger.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the path of the grammar to use.
+/// * `input`: The `input` parameter is the path of the input file to analyze.
+/// * `breakpoints`: The `breakpoints` parameter is the initial list of breakpoints, grammar set names
+///   or line numbers.
+///
+/// Returns:
+///
+/// The exit code of the process once the analysis is over, or an error if the files cannot be read or
+/// the grammar is not valid.
+fn debug(grammar: &str, input: &str, breakpoints: &[String]) -> Result<i32, Error> {
+    let grammar = Grammar::from_path(grammar)?.compile()?;
+    let input = read_input(input)?;
+
+    let breakpoints = breakpoints
+        .iter()
+        .map(|target| Breakpoint::parse(target))
+        .collect();
+    let stdin = std::io::stdin();
+    let mut debugger = Debugger::new(input.as_str(), breakpoints, stdin.lock(), std::io::stdout());
+    let abort = debugger.abort_flag();
+    let stop = abort.clone();
+
+    // the debugger is called with each step of the analysis, and stops it with `quit` or when the
+    // terminal cannot be read or written
+    let mut failure = None;
+    let mut parser = grammar
+        .parser(input.as_str())
+        .with_tracer(Box::new(|event| {
+            if let Err(error) = debugger.on_event(event) {
+                failure = Some(error);
+                stop.store(true, Ordering::Relaxed);
+            }
+        }))
+        .with_abort(abort);
+
+    let accepted = parser.parse();
+    let aborted = parser.aborted();
+    drop(parser);
+
+    if let Some(source) = failure {
+        return Err(Error::io("the terminal", source));
+    }
+    if aborted {
+        println!("⏹️ The analysis was stopped before the end of the input");
+        return Ok(EXIT_REJECTED);
+    }
+
+    match accepted {
+        true => {
+            println!("✅ The input is correct");
+            Ok(EXIT_ACCEPTED)
+        }
+        false => {
+            println!("🚫 The input is incorrect");
+            Ok(EXIT_REJECTED)
+        }
+    }
+}
+
+/// The function `read_input` reads the input file at the given path.
+///
+/// Arguments:
+///
+/// * `path`: The `path` parameter is the path of the input file.
+///
+/// Returns:
+///
+/// The content of the file, or an error if it cannot be read.
+fn read_input(path: &str) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|source| Error::io(path, source))
+}
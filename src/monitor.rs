@@ -0,0 +1,299 @@
+use std::fmt;
+
+use crate::{
+    error::{Error, GrammarErrorKind},
+    parser::Token,
+    store::Store,
+    terminal::Terminal,
+    validator::{Position, Validator},
+};
+
+/// The `Violation` struct represents the event which made a sequence of events leave the language
+/// of the grammar.
+///
+/// Properties:
+///
+/// * `index`: The number of events accepted before the violation.
+/// * `found`: The event which cannot follow the previous ones, or `None` if the sequence ended
+///   before being accepted.
+/// * `expected`: The events which could have come instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation<T = Token> {
+    pub index: usize,
+    pub found: Option<T>,
+    pub expected: Vec<T>,
+}
+
+impl<T: Terminal> fmt::Display for Violation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.found {
+            Some(event) => write!(f, "unexpected {} at event {}", event.name(), self.index)?,
+            None => write!(f, "unexpected end of the events after {}", self.index)?,
+        }
+
+        if self.expected.is_empty() {
+            return Ok(());
+        }
+
+        let names: Vec<String> = self.expected.iter().map(|event| event.name()).collect();
+        write!(f, ", expected {}", names.join(" or "))
+    }
+}
+
+impl<T: Terminal> std::error::Error for Violation<T> {}
+
+impl From<Violation> for Error {
+    /// The `from` function converts a violation into a syntax error, the span of each event being its
+    /// index in the sequence.
+    fn from(violation: Violation) -> Self {
+        Error::Syntax {
+            span: match violation.found {
+                Some(_) => violation.index..violation.index + 1,
+                None => violation.index..violation.index,
+            },
+            found: violation.found,
+            expected: violation.expected,
+        }
+    }
+}
+
+/// The `Monitor` struct checks a sequence of events against a grammar while it is produced, e.g. the
+/// output of an application checked at runtime.
+///
+/// Each event is a token of the grammar given to `step`, which reports as soon as the sequence can
+/// no longer be accepted. `finish` then tells whether the sequence ended in an accepting state. Like
+/// the `Validator`, the monitor keeps every position of the grammar compatible with the events
+/// received so far, so each event is checked without going back over the previous ones.
+///
+/// Properties:
+///
+/// * `positions`: The positions of the grammar compatible with the events received so far.
+/// * `count`: The number of events accepted so far.
+/// * `violation`: The violation which rejected the sequence, if any. The monitor stays rejected
+///   afterwards.
+pub struct Monitor<T = Token> {
+    positions: Vec<Position<T>>,
+    count: usize,
+    violation: Option<Violation<T>>,
+}
+
+impl<T: Terminal> Monitor<T> {
+    /// The function `new` creates a new instance of the `Monitor` struct which, like
+    /// `Parser::parse`, accepts the sequence if a rule of the start set of the store matches it.
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Monitor` struct is being returned.
+    pub fn new(store: &Store<T>) -> Monitor<T> {
+        let positions = store
+            .get_start_rules()
+            .into_iter()
+            .map(|rule| Position {
+                steps: rule.steps,
+                index: 0,
+                values: None,
+            })
+            .collect();
+
+        Self::from_positions(positions)
+    }
+
+    /// The function `from_rule_set` creates a new instance of the `Monitor` struct which accepts the
+    /// sequence only if it matches one of the rules of the given rule set.
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+    /// * `name`: The `name` parameter is the name of the rule set the sequence must match.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Monitor` struct, or an error if the store has no rule set with the
+    /// given name.
+    pub fn from_rule_set(store: &Store<T>, name: &str) -> Result<Monitor<T>, Error> {
+        let rule_set = store.get_rule_set(name).ok_or_else(|| Error::Grammar {
+            set: None,
+            rule: None,
+            kind: GrammarErrorKind::UnknownSet(name.to_string()),
+        })?;
+        let positions = rule_set
+            .lock()
+            .unwrap()
+            .rules
+            .iter()
+            .map(|rule| Position {
+                steps: rule.steps.clone(),
+                index: 0,
+                values: None,
+            })
+            .collect();
+
+        Ok(Self::from_positions(positions))
+    }
+
+    /// The function `from_positions` creates a new instance of the `Monitor` struct starting from the
+    /// given positions.
+    fn from_positions(positions: Vec<Position<T>>) -> Monitor<T> {
+        Monitor {
+            positions: Validator::closure(positions),
+            count: 0,
+            violation: None,
+        }
+    }
+
+    /// The function `step` checks the next event of the sequence.
+    ///
+    /// The events have no text, so only their kind is matched: the constraints of the terminals
+    /// (see `Constraint`) and the predicates of the rules (see `Predicate`) are not checked.
+    ///
+    /// Arguments:
+    ///
+    /// * `event`: The `event` parameter is the event emitted by the application.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the events received so far are still the beginning of an accepted sequence, or the
+    /// violation which rejected the sequence otherwise.
+    pub fn step(&mut self, event: T) -> Result<(), Violation<T>> {
+        if let Some(violation) = &self.violation {
+            return Err(violation.clone());
+        }
+
+        let positions = Validator::advance(&self.positions, &event, None);
+        if positions.is_empty() {
+            let violation = Violation {
+                index: self.count,
+                found: Some(event),
+                expected: self.expected(),
+            };
+            self.violation = Some(violation.clone());
+            return Err(violation);
+        }
+
+        self.positions = positions;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// The function `finish` ends the sequence of events.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the sequence is accepted by the grammar, or the violation which rejected it
+    /// otherwise.
+    pub fn finish(self) -> Result<(), Violation<T>> {
+        if let Some(violation) = self.violation {
+            return Err(violation);
+        }
+
+        match self.is_accepting() {
+            true => Ok(()),
+            false => Err(Violation {
+                index: self.count,
+                found: None,
+                expected: self.expected(),
+            }),
+        }
+    }
+
+    /// The function `expected` returns the events which may come after the ones received so far.
+    ///
+    /// Returns:
+    ///
+    /// The events expected by the grammar, without duplicates, in the order of the grammar.
+    pub fn expected(&self) -> Vec<T> {
+        Validator::expected_from(&self.positions)
+    }
+
+    /// The function `is_accepting` returns true if the sequence would be accepted if it ended now,
+    /// the terminal of the end of the input matching the end of the sequence.
+    pub fn is_accepting(&self) -> bool {
+        let end: Vec<T> = self
+            .expected()
+            .into_iter()
+            .filter(|event| event.is_end())
+            .collect();
+        let ended = end
+            .iter()
+            .flat_map(|event| Validator::advance(&self.positions, event, None));
+
+        self.positions
+            .iter()
+            .cloned()
+            .chain(ended)
+            .any(|position| position.index == position.steps.lock().unwrap().len())
+    }
+
+    /// The function `count` returns the number of events accepted so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::GrammarBuilder;
+
+    fn monitor() -> Monitor {
+        GrammarBuilder::new()
+            .set("S", |set| set.then("C"))
+            .set("C", |set| {
+                set.rule(["Contact", "Identifier", "Number"])
+                    .then("C")
+                    .rule(["End"])
+            })
+            .build()
+            .unwrap()
+            .compile()
+            .unwrap()
+            .monitor()
+    }
+
+    #[test]
+    fn accepts_a_sequence_of_events() {
+        let mut monitor = monitor();
+
+        for event in [Token::Contact, Token::Identifier, Token::Number] {
+            monitor.step(event).unwrap();
+        }
+        assert!(monitor.is_accepting());
+        assert_eq!(monitor.count(), 3);
+        assert_eq!(monitor.finish(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_an_unexpected_event() {
+        let mut monitor = monitor();
+
+        monitor.step(Token::Contact).unwrap();
+        let violation = Violation {
+            index: 1,
+            found: Some(Token::Rate),
+            expected: vec![Token::Identifier],
+        };
+        assert_eq!(monitor.step(Token::Rate), Err(violation.clone()));
+        // the violation is kept
+        assert_eq!(monitor.step(Token::Identifier), Err(violation));
+    }
+
+    #[test]
+    fn rejects_a_truncated_sequence() {
+        let mut monitor = monitor();
+
+        monitor.step(Token::Contact).unwrap();
+        assert!(!monitor.is_accepting());
+        assert_eq!(
+            monitor.finish(),
+            Err(Violation {
+                index: 1,
+                found: None,
+                expected: vec![Token::Identifier],
+            })
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::path::PathBuf;
+
+use crate::{
+    capture,
+    error::Error,
+    grammar::{Grammar, GrammarRule, GrammarSet},
+    template,
+};
+
+/// The function `parse` reads a grammar written in the text notation printed by the `Display` of
+/// `Grammar`, e.g.:
+///
+/// ```text
+/// S -> C
+/// C -> contact identifier identifier number number C | None
+/// sync: contact
+/// ```
+///
+/// Each line defines a grammar set and its rules separated by `|`. The terminals are written in
+/// lowercase, the grammar set which may end a rule starts with an uppercase letter, and `None` is a
+/// rule without terminal. A terminal may be followed by a constraint between brackets, e.g.
+/// `number[0..100]`, inside which `|` and spaces separate neither rules nor words, and preceded by
+/// the name capturing its text, e.g. `from:identifier`. The attributes of a rule are written at its
+/// end between braces and separated by `;`, e.g. `rate R { count = 1 + next.count }`, along with
+/// the predicates the rule requires, e.g. `{ require ordered: start < end }`, and the identifiers
+/// it declares or uses, e.g. `{ declare node: name }` or `{ use node: from, to }`, and its output
+/// template between double quotes, e.g. `{ emit "${from} -> ${to}" }`. The optional
+/// `sync:` line lists the synchronization terminals, and the empty lines and the lines starting with
+/// `#` are ignored.
+///
+/// Arguments:
+///
+/// * `text`: The `text` parameter is the grammar in the text notation.
+/// * `path`: The `path` parameter is the path of the file the grammar was read from, if any.
+///
+/// Returns:
+///
+/// The `Grammar`, or a `GrammarSyntax` error locating the first line which cannot be read.
+pub fn parse(text: &str, path: Option<PathBuf>) -> Result<Grammar, Error> {
+    let mut grammar = Grammar {
+        sets: Vec::new(),
+        sync: Vec::new(),
+    };
+
+    for (index, line) in text.lines().enumerate() {
+        let error = |column: usize, message: &str| Error::GrammarSyntax {
+            path: path.clone(),
+            line: index + 1,
+            column,
+            message: message.to_string(),
+        };
+
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+
+        if let Some(sync) = content.strip_prefix("sync:") {
+            grammar
+                .sync
+                .extend(sync.split_whitespace().map(terminal_name));
+            continue;
+        }
+
+        let Some((name, rules)) = line.split_once("->") else {
+            return Err(error(1, "expected `->` after the name of the set"));
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(error(1, "expected the name of the set before `->`"));
+        }
+
+        let mut set = GrammarSet {
+            name: name.to_string(),
+            rules: Vec::new(),
+        };
+        // the column of the current rule, starting after the arrow
+        let mut column = line.find("->").unwrap() + 3;
+
+        for rule in split(rules, |c| c == '|') {
+            let mut words: Vec<&str> = split(rule, char::is_whitespace)
+                .into_iter()
+                .filter(|word| !word.is_empty())
+                .collect();
+
+            // the predicates, the symbols, the attributes and the template of the rule are written
+            // at its end between braces
+            let mut predicates: Vec<String> = Vec::new();
+            let mut symbols: Vec<String> = Vec::new();
+            let mut attributes: Vec<String> = Vec::new();
+            let mut template: Option<String> = None;
+            if let Some(word) = words.last().filter(|word| word.starts_with('{')) {
+                let Some(block) = word[1..].strip_suffix('}') else {
+                    return Err(error(column, "expected `}` after the attributes"));
+                };
+                words.pop();
+
+                let entries = split(block, |c| c == ';');
+                for entry in entries.into_iter().map(str::trim).filter(|e| !e.is_empty()) {
+                    if let Some(text) = entry.strip_prefix("emit ") {
+                        if template.is_some() {
+                            return Err(error(column, "expected a single `emit` per rule"));
+                        }
+                        let text = template::unquote(text.trim())
+                            .map_err(|message| error(column, message.as_str()))?;
+                        template = Some(text);
+                        continue;
+                    }
+
+                    match entry.strip_prefix("require ") {
+                        Some(predicate) => predicates.push(predicate.trim().to_string()),
+                        None if is_symbol(entry) => symbols.push(entry.to_string()),
+                        None => attributes.push(entry.to_string()),
+                    }
+                }
+            }
+
+            match words.as_slice() {
+                [] => return Err(error(column, "expected a rule, or `None` for an empty one")),
+                ["None"] => set.rules.push(GrammarRule {
+                    terminals: Vec::new(),
+                    non_terminal: None,
+                    predicates,
+                    symbols,
+                    attributes,
+                    template,
+                }),
+                [terminals @ .., last] => {
+                    // only the last word may be a grammar set
+                    if let Some(word) = terminals.iter().find(|word| is_set(word)) {
+                        let message = format!("the set `{}` must end the rule", word);
+                        return Err(error(column, message.as_str()));
+                    }
+
+                    let mut terminals: Vec<String> =
+                        terminals.iter().map(|word| terminal_name(word)).collect();
+                    let non_terminal = match is_set(last) {
+                        true => Some(last.to_string()),
+                        false => {
+                            terminals.push(terminal_name(last));
+                            None
+                        }
+                    };
+
+                    set.rules.push(GrammarRule {
+                        terminals,
+                        non_terminal,
+                        predicates,
+                        symbols,
+                        attributes,
+                        template,
+                    });
+                }
+            }
+
+            column += rule.len() + 1;
+        }
+
+        grammar.sets.push(set);
+    }
+
+    Ok(grammar)
+}
+
+/// The function `split` splits the text on the characters matching the separator which are not
+/// between the brackets of a constraint, the braces of the attributes or the double quotes of a
+/// template.
+///
+/// Arguments:
+///
+/// * `text`: The `text` parameter is the text to split.
+/// * `separator`: The `separator` parameter returns true for the characters separating the parts.
+///
+/// Returns:
+///
+/// The parts of the text, possibly empty.
+fn split(text: &str, separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut brackets = 0usize;
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        // the quotes escaped in a template do not end it
+        if quoted {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            // a quote in a constraint, e.g. in a pattern, is not a template
+            '"' if brackets == 0 => quoted = true,
+            '[' => (depth, brackets) = (depth + 1, brackets + 1),
+            ']' => (depth, brackets) = (depth.saturating_sub(1), brackets.saturating_sub(1)),
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            c if depth == 0 && separator(c) => {
+                parts.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+
+    parts
+}
+
+/// The function `is_symbol` returns true if the entry between the braces of a rule is a symbol
+/// entry, e.g. `use node: from`, rather than an attribute, which always contains a `=`.
+fn is_symbol(entry: &str) -> bool {
+    entry == "scope"
+        || (["declare ", "use "]
+            .iter()
+            .any(|action| entry.starts_with(action))
+            && !entry.contains('='))
+}
+
+/// The function `is_set` returns true if the word of a rule is a grammar set, i.e. it starts with an
+/// uppercase letter.
+fn is_set(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_uppercase())
+}
+
+/// The function `terminal_name` converts a terminal written in lowercase into its name in the grammar,
+/// e.g. `identifier` into `Identifier` and `from:identifier` into `from:Identifier`.
+fn terminal_name(word: &str) -> String {
+    let (capture, word) = capture::split(word);
+    let mut chars = word.chars();
+    let name: String = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+
+    match capture {
+        Some(capture) => format!("{}:{}", capture, name),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The function `syntax_error` returns the line, the column and the message of the error of a
+    /// grammar which cannot be read.
+    fn syntax_error(text: &str) -> (usize, usize, String) {
+        match parse(text, None) {
+            Err(Error::GrammarSyntax {
+                line,
+                column,
+                message,
+                ..
+            }) => (line, column, message),
+            result => panic!("expected a syntax error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn reads_back_the_printed_grammar() {
+        let text = concat!(
+            "S -> N\n",
+            "N -> identifier[node] name:identifier N { declare node: name } | Contacts\n",
+            "Contacts -> contact from:identifier to:identifier start:number[0..86400] ",
+            "end:number Contacts { require ordered: start < end; use node: from, to; ",
+            "duration = end - start; emit \"${from} | ${to}: \\\"${duration}\\\"\" } | None\n",
+            "sync: contact rate\n",
+        );
+
+        let grammar = parse(text, None).unwrap();
+        assert_eq!(grammar.to_string(), text);
+        assert_eq!(parse(&grammar.to_string(), None).unwrap().to_string(), text);
+
+        let rule = &grammar.sets[2].rules[0];
+        assert_eq!(rule.non_terminal.as_deref(), Some("Contacts"));
+        assert_eq!(rule.terminals[3], "start:Number[0..86400]");
+        assert_eq!(
+            rule.template.as_deref(),
+            Some("${from} | ${to}: \"${duration}\"")
+        );
+        assert_eq!(grammar.sync, vec!["Contact", "Rate"]);
+    }
+
+    #[test]
+    fn ignores_the_comments_and_the_empty_lines() {
+        let grammar = parse(
+            "# contacts\n\nS -> C\n  # none\nC -> contact C | None\n",
+            None,
+        );
+        assert_eq!(
+            grammar.unwrap().to_string(),
+            "S -> C\nC -> contact C | None\n"
+        );
+    }
+
+    #[test]
+    fn locates_the_syntax_errors() {
+        assert_eq!(
+            syntax_error("S -> C\nC contact"),
+            (2, 1, "expected `->` after the name of the set".to_string())
+        );
+        assert_eq!(
+            syntax_error("S C -> contact"),
+            (1, 1, "expected the name of the set before `->`".to_string())
+        );
+
+        // the column is the start of the rule, after the arrow or the previous `|`
+        assert_eq!(
+            syntax_error("S -> contact | C contact"),
+            (1, 15, "the set `C` must end the rule".to_string())
+        );
+        assert_eq!(
+            syntax_error("S -> contact |  | None"),
+            (
+                1,
+                15,
+                "expected a rule, or `None` for an empty one".to_string()
+            )
+        );
+        assert_eq!(
+            syntax_error("S -> contact { a = 1"),
+            (1, 5, "expected `}` after the attributes".to_string())
+        );
+        assert_eq!(
+            syntax_error("S -> None\nC -> None { emit \"a\"; emit \"b\" }"),
+            (2, 5, "expected a single `emit` per rule".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_only_the_capitalized_words_as_sets() {
+        // a set is named as it is defined, a lowercase word being a terminal
+        let grammar = parse("S -> C | c\nC -> None", None).unwrap();
+        assert_eq!(grammar.sets[0].rules[0].non_terminal.as_deref(), Some("C"));
+        assert_eq!(grammar.sets[0].rules[1].non_terminal, None);
+        assert_eq!(grammar.sets[0].rules[1].terminals, vec!["C"]);
+    }
+}
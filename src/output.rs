@@ -0,0 +1,698 @@
+use serde::Serialize;
+
+use crate::{
+    attribute::Attributes,
+    error::Error,
+    plan::Finding,
+    records::RecordReport,
+    report::{location, Report},
+};
+
+/// The `Output` struct is the machine-readable result of a validation, printed by the command line
+/// with `--format json`.
+///
+/// Properties:
+///
+/// * `status`: Whether the input is accepted, rejected, or could not be validated.
+/// * `grammar`: The grammar the input was validated with.
+/// * `input`: The path of the validated input, if known.
+/// * `errors`: The errors found in the input, or the error which prevented the validation.
+/// * `records`: The result of each line of the input in record mode.
+/// * `attributes`: The attributes of the rules which matched the input, if any.
+/// * `findings`: The inconsistencies of the input read as a contact plan, if it was checked.
+/// * `timing`: The time spent compiling the grammar and validating the input, if the validation ran.
+#[derive(Debug, Clone, Serialize)]
+pub struct Output {
+    pub status: Status,
+    pub grammar: GrammarOutput,
+    pub input: Option<String>,
+    pub errors: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub records: Option<Vec<RecordOutput>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Attributes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub findings: Option<Vec<Finding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+/// The `Status` enum represents the outcome of a validation.
+///
+/// Variants:
+///
+/// * `Accepted`: The input is accepted by the grammar.
+/// * `Rejected`: The input is not accepted by the grammar.
+/// * `Error`: The input could not be validated, e.g. the grammar is not valid.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Accepted,
+    Rejected,
+    Error,
+}
+
+impl From<bool> for Status {
+    fn from(accepted: bool) -> Self {
+        match accepted {
+            true => Status::Accepted,
+            false => Status::Rejected,
+        }
+    }
+}
+
+/// The `GrammarOutput` struct identifies the grammar used for a validation.
+///
+/// Properties:
+///
+/// * `path`: The path of the grammar file, if known.
+/// * `fingerprint`: The fingerprint of the grammar (see `Grammar::fingerprint`), if it could be read.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GrammarOutput {
+    pub path: Option<String>,
+    pub fingerprint: Option<String>,
+}
+
+/// The `Diagnostic` struct is an error of the `Output`, located in the input when possible.
+///
+/// Properties:
+///
+/// * `kind`: The category of the error (see `Error::category`).
+/// * `message`: The description of the error.
+/// * `span`: The byte span of the error in the input, if any.
+/// * `line`: The line of the start of the span, starting at 1.
+/// * `column`: The column of the start of the span, in characters, starting at 1.
+/// * `found`: The unexpected token, `None` at the end of the input or for other errors.
+/// * `expected`: The tokens which could have come instead.
+/// * `previous`: The byte span of the previous declaration of a redeclared identifier.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub kind: String,
+    pub message: String,
+    pub span: Option<Span>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub found: Option<String>,
+    pub expected: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Span>,
+}
+
+/// The `Span` struct is a byte span of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The `RecordOutput` struct is the result of a line of the input in record mode.
+///
+/// Properties:
+///
+/// * `line`: The number of the line, starting at 1.
+/// * `accepted`: True if the line matches the record rule set.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordOutput {
+    pub line: usize,
+    pub accepted: bool,
+}
+
+/// The `Timing` struct is the time spent by a validation, in milliseconds.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Timing {
+    pub compile_ms: f64,
+    pub validate_ms: f64,
+}
+
+impl Diagnostic {
+    /// The function `new` creates a new instance of the `Diagnostic` struct from an error of the
+    /// crate.
+    ///
+    /// Arguments:
+    ///
+    /// * `error`: The `error` parameter is the error to describe.
+    /// * `input`: The `input` parameter is the validated input, used to locate the error.
+    /// * `base`: The `base` parameter is the byte offset in the input the span of the error is
+    ///   relative to, e.g. the start of a record.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Diagnostic` struct is being returned.
+    pub fn new(error: &Error, input: &str, base: usize) -> Diagnostic {
+        Diagnostic::describe(error, Some(input), base)
+    }
+
+    /// The function `describe` creates a new instance of the `Diagnostic` struct, locating the error
+    /// on its line and column only if the input is known.
+    ///
+    /// Arguments:
+    ///
+    /// * `error`: The `error` parameter is the error to describe.
+    /// * `input`: The `input` parameter is the validated input, if any.
+    /// * `base`: The `base` parameter is the byte offset in the input the span of the error is
+    ///   relative to.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Diagnostic` struct is being returned.
+    fn describe(error: &Error, input: Option<&str>, base: usize) -> Diagnostic {
+        // locate the error in the whole input
+        let mut error = error.clone();
+        if let Error::Lex { span }
+        | Error::Syntax { span, .. }
+        | Error::Constraint { span, .. }
+        | Error::Predicate { span, .. }
+        | Error::Undeclared { span, .. }
+        | Error::Attribute { span, .. } = &mut error
+        {
+            *span = span.start + base..span.end + base;
+        }
+        if let Error::Redeclared { span, previous, .. } = &mut error {
+            *span = span.start + base..span.end + base;
+            *previous = previous.start + base..previous.end + base;
+        }
+
+        let span = error.span().map(|span| Span {
+            start: span.start,
+            end: span.end,
+        });
+        let location = input
+            .zip(span)
+            .map(|(input, span)| location(input, span.start));
+
+        let (found, expected) = match &error {
+            Error::Syntax {
+                found, expected, ..
+            } => (
+                found.as_ref().map(|token| format!("{:?}", token)),
+                expected
+                    .iter()
+                    .map(|token| format!("{:?}", token))
+                    .collect(),
+            ),
+            Error::Constraint {
+                found, expected, ..
+            } => (Some(format!("{:?}", found)), expected.clone()),
+            _ => (None, Vec::new()),
+        };
+        let previous = match &error {
+            Error::Redeclared { previous, .. } => Some(Span {
+                start: previous.start,
+                end: previous.end,
+            }),
+            _ => None,
+        };
+
+        Diagnostic {
+            kind: error.category().to_string(),
+            message: error.to_string(),
+            span,
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            found,
+            expected,
+            previous,
+        }
+    }
+}
+
+impl Output {
+    /// The function `from_report` creates the `Output` of the validation of a whole input.
+    ///
+    /// Arguments:
+    ///
+    /// * `report`: The `report` parameter is the result of the validation.
+    /// * `input`: The `input` parameter is the validated input.
+    ///
+    /// Returns:
+    ///
+    /// An `Output` without grammar, input path and timing, to be filled by the caller.
+    pub fn from_report(report: &Report, input: &str) -> Output {
+        Output {
+            status: Status::from(report.accepted),
+            grammar: GrammarOutput::default(),
+            input: None,
+            errors: report
+                .errors
+                .iter()
+                .map(|error| Diagnostic::new(error, input, 0))
+                .collect(),
+            records: None,
+            attributes: report.attributes.clone(),
+            findings: None,
+            timing: None,
+        }
+    }
+
+    /// The function `from_records` creates the `Output` of the validation of an input in record
+    /// mode. The errors of the lines come first, followed by the error of the sequence of records.
+    ///
+    /// Arguments:
+    ///
+    /// * `report`: The `report` parameter is the result of the validation of the records.
+    /// * `input`: The `input` parameter is the validated input.
+    ///
+    /// Returns:
+    ///
+    /// An `Output` without grammar, input path and timing, to be filled by the caller.
+    pub fn from_records(report: &RecordReport, input: &str) -> Output {
+        // the byte offset of the start of each line, the errors of a record being relative to it
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        let mut errors: Vec<Diagnostic> = report
+            .lines
+            .iter()
+            .filter_map(|line| {
+                let error = line.error.as_ref()?;
+                Some(Diagnostic::new(error, input, starts[line.number - 1]))
+            })
+            .collect();
+        if let Some(Err((_, error))) = &report.structure {
+            errors.push(Diagnostic::new(error, input, 0));
+        }
+
+        Output {
+            status: Status::from(report.is_correct()),
+            grammar: GrammarOutput::default(),
+            input: None,
+            errors,
+            records: Some(
+                report
+                    .lines
+                    .iter()
+                    .map(|line| RecordOutput {
+                        line: line.number,
+                        accepted: line.error.is_none(),
+                    })
+                    .collect(),
+            ),
+            attributes: Vec::new(),
+            findings: None,
+            timing: None,
+        }
+    }
+
+    /// The function `from_error` creates the `Output` of a validation which could not run.
+    ///
+    /// Arguments:
+    ///
+    /// * `error`: The `error` parameter is the error which prevented the validation.
+    ///
+    /// Returns:
+    ///
+    /// An `Output` without grammar and input path, to be filled by the caller. The error is not
+    /// located on a line and a column, the input not being known.
+    pub fn from_error(error: &Error) -> Output {
+        Output {
+            status: Status::Error,
+            grammar: GrammarOutput::default(),
+            input: None,
+            errors: vec![Diagnostic::describe(error, None, 0)],
+            records: None,
+            attributes: Vec::new(),
+            findings: None,
+            timing: None,
+        }
+    }
+
+    /// The function `to_json` formats the output as JSON.
+    ///
+    /// Returns:
+    ///
+    /// The output as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        // the output only contains strings, numbers and options, it is always serializable
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// The function `to_json` formats the outputs of a batch of inputs as JSON.
+///
+/// Arguments:
+///
+/// * `outputs`: The `outputs` parameter is the output of each input.
+///
+/// Returns:
+///
+/// The output as a JSON object for a single input, or a JSON array of outputs otherwise.
+pub fn to_json(outputs: &[Output]) -> String {
+    match outputs {
+        [output] => output.to_json(),
+        _ => serde_json::to_string_pretty(outputs).unwrap(),
+    }
+}
+
+/// The function `to_sarif` formats the outputs of a batch of inputs as a SARIF 2.1.0 log, each
+/// error being a result located in the input (or in the grammar for grammar errors).
+///
+/// Arguments:
+///
+/// * `outputs`: The `outputs` parameter is the output of each input.
+///
+/// Returns:
+///
+/// The SARIF log as a pretty-printed JSON document.
+pub fn to_sarif(outputs: &[Output]) -> String {
+    let rules = [
+        ("io", "A file cannot be read"),
+        ("grammar-syntax", "The grammar is not valid JSON"),
+        ("grammar", "The grammar is not valid"),
+        ("lex", "The input contains an invalid token"),
+        ("syntax", "The input is not accepted by the grammar"),
+        (
+            "constraint",
+            "A token does not satisfy the constraint of its terminal",
+        ),
+        ("predicate", "A predicate of a rule does not hold"),
+        (
+            "attribute",
+            "An attribute of a matched rule cannot be computed",
+        ),
+        ("undeclared", "An identifier is used before being declared"),
+        (
+            "redeclared",
+            "An identifier is declared twice in the same scope",
+        ),
+    ];
+
+    let mut results = Vec::new();
+    for output in outputs {
+        for diagnostic in &output.errors {
+            // the grammar errors are located in the grammar, the other ones in the input
+            let uri = match diagnostic.kind.starts_with("grammar") {
+                true => output.grammar.path.as_ref(),
+                false => output.input.as_ref(),
+            };
+
+            let mut location = serde_json::json!({
+                "physicalLocation": { "artifactLocation": { "uri": uri } }
+            });
+            if let (Some(span), Some(line), Some(column)) =
+                (diagnostic.span, diagnostic.line, diagnostic.column)
+            {
+                location["physicalLocation"]["region"] = serde_json::json!({
+                    "startLine": line,
+                    "startColumn": column,
+                    "byteOffset": span.start,
+                    "byteLength": span.end - span.start,
+                });
+            }
+
+            results.push(serde_json::json!({
+                "ruleId": diagnostic.kind,
+                "level": "error",
+                "message": { "text": diagnostic.message },
+                "locations": [location],
+            }));
+        }
+    }
+
+    let sarif = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                        .iter()
+                        .map(|(id, description)| serde_json::json!({
+                            "id": id,
+                            "shortDescription": { "text": description },
+                        }))
+                        .collect::<Vec<_>>(),
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }]
+    });
+
+    serde_json::to_string_pretty(&sarif).unwrap()
+}
+
+/// The function `to_junit` formats the outputs of a batch of inputs as a JUnit XML report, each
+/// input being a test case of the test suite of its grammar. A rejected input is a failure, and an
+/// input which could not be validated is an error.
+///
+/// Arguments:
+///
+/// * `outputs`: The `outputs` parameter is the output of each input.
+///
+/// Returns:
+///
+/// The JUnit XML report.
+pub fn to_junit(outputs: &[Output]) -> String {
+    let count = |status| {
+        outputs
+            .iter()
+            .filter(|output| output.status == status)
+            .count()
+    };
+    let time: f64 = outputs
+        .iter()
+        .filter_map(|output| output.timing)
+        .map(|timing| timing.validate_ms / 1000.0)
+        .fold(0.0, |total, time| total + time);
+    let suite = outputs
+        .first()
+        .and_then(|output| output.grammar.path.clone())
+        .unwrap_or_default();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        env!("CARGO_PKG_NAME"),
+        outputs.len(),
+        count(Status::Rejected),
+        count(Status::Error),
+        time
+    );
+    xml += &format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+        escape(&suite),
+        outputs.len(),
+        count(Status::Rejected),
+        count(Status::Error),
+        time
+    );
+
+    for output in outputs {
+        xml += &format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            escape(output.input.as_deref().unwrap_or_default()),
+            escape(&suite),
+            output
+                .timing
+                .map_or(0.0, |timing| timing.validate_ms / 1000.0)
+        );
+
+        let tag = match output.status {
+            Status::Accepted => {
+                xml += "/>\n";
+                continue;
+            }
+            Status::Rejected => "failure",
+            Status::Error => "error",
+        };
+
+        // the first error is the message, every error is in the body
+        let first = output.errors.first();
+        let body: Vec<String> = output
+            .errors
+            .iter()
+            .map(|diagnostic| match (diagnostic.line, diagnostic.column) {
+                (Some(line), Some(column)) => {
+                    format!("{}:{}: {}", line, column, diagnostic.message)
+                }
+                _ => diagnostic.message.clone(),
+            })
+            .collect();
+
+        xml += ">\n";
+        xml += &format!(
+            "      <{} message=\"{}\" type=\"{}\">{}</{}>\n",
+            tag,
+            escape(first.map_or("", |diagnostic| diagnostic.message.as_str())),
+            escape(first.map_or("", |diagnostic| diagnostic.kind.as_str())),
+            escape(&body.join("\n")),
+            tag
+        );
+        xml += "    </testcase>\n";
+    }
+
+    xml += "  </testsuite>\n";
+    xml += "</testsuites>";
+    xml
+}
+
+/// The function `escape` escapes the special characters of XML in a text or an attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::GrammarErrorKind, grammar::Grammar, report};
+
+    const GRAMMAR: &str = "S -> C\nC -> contact identifier number C | None";
+
+    fn output(input: &str) -> Output {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+        let report = report::validate(&grammar, input).unwrap();
+        Output::from_report(&report, input)
+    }
+
+    fn template_error() -> Error {
+        Error::Grammar {
+            set: Some("S".to_string()),
+            rule: Some(0),
+            kind: GrammarErrorKind::InvalidTemplate {
+                template: "say \"${x}\" <&>\n\\".to_string(),
+                message: "unknown name `x`".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn reports_the_errors_as_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&output("contact A 10\ncontact 20").to_json()).unwrap();
+
+        assert_eq!(json["status"], "rejected");
+        assert_eq!(
+            json["errors"],
+            serde_json::json!([{
+                "kind": "syntax",
+                "message": "unexpected Number, expected Identifier",
+                "span": { "start": 21, "end": 23 },
+                "line": 2,
+                "column": 9,
+                "found": "Number",
+                "expected": ["Identifier"],
+            }])
+        );
+        assert!(json.get("records").is_none() && json.get("timing").is_none());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&output("contact A 10\n").to_json()).unwrap();
+        assert_eq!(json["status"], "accepted");
+        assert_eq!(json["errors"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn escapes_the_messages_in_json() {
+        let error = template_error();
+        let json: serde_json::Value =
+            serde_json::from_str(&Output::from_error(&error).to_json()).unwrap();
+
+        assert_eq!(json["status"], "error");
+        assert_eq!(json["errors"][0]["kind"], "grammar");
+        assert_eq!(json["errors"][0]["message"], error.to_string());
+        assert!(json["errors"][0]["line"].is_null());
+    }
+
+    #[test]
+    fn does_not_locate_an_error_without_input() {
+        // the span of an error of the input is kept, but the input is not there to locate it
+        let error = Error::Syntax {
+            span: 40..42,
+            found: None,
+            expected: Vec::new(),
+        };
+        let output = Output::from_error(&error);
+
+        let diagnostic = &output.errors[0];
+        assert_eq!(diagnostic.span, Some(Span { start: 40, end: 42 }));
+        assert_eq!((diagnostic.line, diagnostic.column), (None, None));
+    }
+
+    #[test]
+    fn reports_a_batch_as_a_json_array() {
+        let single: serde_json::Value = serde_json::from_str(&to_json(&[output("")])).unwrap();
+        assert!(single.is_object());
+
+        let batch: serde_json::Value =
+            serde_json::from_str(&to_json(&[output(""), output("contact")])).unwrap();
+        assert_eq!(batch[0]["status"], "accepted");
+        assert_eq!(batch[1]["status"], "rejected");
+    }
+
+    #[test]
+    fn reports_the_errors_as_sarif() {
+        let mut output = output("contact A 1 contact é 2 ü");
+        output.input = Some("input.txt".to_string());
+        let sarif: serde_json::Value = serde_json::from_str(&to_sarif(&[output])).unwrap();
+
+        let run = &sarif["runs"][0];
+        assert_eq!(run["columnKind"], "unicodeCodePoints");
+        assert_eq!(run["results"].as_array().unwrap().len(), 2);
+
+        // the column counts the characters before the token, and the offset their bytes
+        let result = &run["results"][1];
+        assert_eq!(result["ruleId"], "lex");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            serde_json::json!({
+                "artifactLocation": { "uri": "input.txt" },
+                "region": {
+                    "startLine": 1,
+                    "startColumn": 25,
+                    "byteOffset": 25,
+                    "byteLength": 2,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn locates_the_grammar_errors_in_the_grammar_in_sarif() {
+        let mut output = Output::from_error(&template_error());
+        output.grammar.path = Some("grammar.txt".to_string());
+        let sarif: serde_json::Value = serde_json::from_str(&to_sarif(&[output])).unwrap();
+
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["message"]["text"], template_error().to_string());
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            serde_json::json!({ "artifactLocation": { "uri": "grammar.txt" } })
+        );
+    }
+
+    #[test]
+    fn reports_the_inputs_as_junit_test_cases() {
+        let mut outputs = vec![
+            output("contact A 1"),
+            output("contact 2"),
+            Output::from_error(&template_error()),
+        ];
+        for (output, name) in outputs.iter_mut().zip(["a.txt", "b.txt", "c.txt"]) {
+            output.grammar.path = Some("grammar.txt".to_string());
+            output.input = Some(name.to_string());
+        }
+        let xml = to_junit(&outputs);
+
+        assert!(xml.contains(r#"<testsuite name="grammar.txt" tests="3" failures="1" errors="1""#));
+        assert!(xml.contains(r#"<testcase name="a.txt" classname="grammar.txt" time="0.000"/>"#));
+        assert!(xml.contains(
+            r#"<failure message="unexpected Number, expected Identifier" type="syntax">1:9: unexpected Number, expected Identifier</failure>"#
+        ));
+        assert!(xml.contains(r#"<error message="invalid grammar in S #0: invalid template `say &quot;${x}&quot; &lt;&amp;&gt;"#));
+    }
+
+    #[test]
+    fn escapes_the_special_characters_of_xml() {
+        assert_eq!(
+            escape(r#"a & b < c > "d" 'e'"#),
+            "a &amp; b &lt; c &gt; &quot;d&quot; &apos;e&apos;"
+        );
+    }
+}
@@ -1,10 +1,20 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
 
 use logos::{Lexer, Logos};
 
 use crate::{
+    expression::Value,
+    lexer::Lexeme,
     rules::{Rule, RuleStep},
     store::Store,
+    terminal::{Terminal, Tokens},
 };
 
 #[derive(Logos, Debug, PartialEq, Clone)]
@@ -14,8 +24,10 @@ pub enum Token {
     Contact,
 
     #[token("rate")]
+    Rate,
+
     #[token("delay")]
-    Options,
+    Delay,
 
     #[regex("[a-zA-Z]+")]
     Identifier,
@@ -36,59 +48,370 @@ impl Token {
     ///
     /// Returns:
     ///
-    /// a value of type `Token`.
-    pub fn from_string(s: &str) -> Token {
+    /// a value of type `Token`, or `None` if the string is not the name of a token.
+    pub fn from_string(s: &str) -> Option<Token> {
         match s {
-            "Contact" => Token::Contact,
-            "Rate" => Token::Options,
-            "Delay" => Token::Options,
-            "Identifier" => Token::Identifier,
-            "Number" => Token::Number,
-            "End" => Token::End,
-            _ => panic!("Invalid token"),
+            "Contact" => Some(Token::Contact),
+            "Rate" => Some(Token::Rate),
+            "Delay" => Some(Token::Delay),
+            "Identifier" => Some(Token::Identifier),
+            "Number" => Some(Token::Number),
+            "End" => Some(Token::End),
+            _ => None,
+        }
+    }
+}
+
+impl Terminal for Token {
+    fn from_name(name: &str) -> Option<Self> {
+        Token::from_string(name)
+    }
+
+    fn is_end(&self) -> bool {
+        *self == Token::End
+    }
+
+    fn value(&self, text: &str) -> Option<u64> {
+        match self {
+            Token::Number => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// The `Event` enum represents a step of the analysis made by the `Parser`, reported to its tracer.
+///
+/// Variants:
+///
+/// * `Try`: The rule `index` of the grammar set `name` is tried, the `remaining` rules (grammar set
+///   and index) will be tried if it does not match.
+/// * `Consume`: The `lexeme` at the given byte span matched the current step of the rule.
+/// * `Mismatch`: The `lexeme` at the given byte span does not match the current step of the rule,
+///   which `expected` another token or the end of the rule (`None`).
+/// * `Enter`: The current step of the rule is a reference to the grammar set `name`.
+/// * `Check`: The predicate `name` of the rule is checked over the terminals it matched, and it
+///   `holds` or not.
+/// * `Backtrack`: The rule `index` of the grammar set `name` does not match, the next one is tried.
+/// * `Accept`: The rule `index` of the grammar set `name` matches the rest of the input.
+///
+/// The `lexeme` of `Consume` and `Mismatch` is `None` at the end of the input. The `depth` of each
+/// variant is the number of grammar sets entered before the event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<T = Token> {
+    Try {
+        name: String,
+        index: usize,
+        remaining: Vec<(String, usize)>,
+        depth: usize,
+    },
+    Consume {
+        lexeme: Option<Lexeme<T>>,
+        span: Range<usize>,
+        depth: usize,
+    },
+    Mismatch {
+        lexeme: Option<Lexeme<T>>,
+        span: Range<usize>,
+        expected: Option<T>,
+        depth: usize,
+    },
+    Enter {
+        name: String,
+        depth: usize,
+    },
+    Check {
+        name: String,
+        holds: bool,
+        depth: usize,
+    },
+    Backtrack {
+        name: String,
+        index: usize,
+        depth: usize,
+    },
+    Accept {
+        name: String,
+        index: usize,
+        depth: usize,
+    },
+}
+
+impl<T: Terminal> Event<T> {
+    /// The function `depth` returns the number of grammar sets entered before the event.
+    pub fn depth(&self) -> usize {
+        match self {
+            Event::Try { depth, .. }
+            | Event::Consume { depth, .. }
+            | Event::Mismatch { depth, .. }
+            | Event::Enter { depth, .. }
+            | Event::Check { depth, .. }
+            | Event::Backtrack { depth, .. }
+            | Event::Accept { depth, .. } => *depth,
+        }
+    }
+
+    /// The function `describe` returns the name and the text of the lexeme of a `Consume` or
+    /// `Mismatch` event, `End` being the end of the input.
+    pub fn describe(lexeme: &Option<Lexeme<T>>) -> String {
+        match lexeme
+            .as_ref()
+            .and_then(|lexeme| Some((lexeme.token.as_ref()?, lexeme)))
+        {
+            Some((token, lexeme)) => format!("{} {:?}", token.name(), lexeme.text),
+            None => "End".to_string(),
+        }
+    }
+}
+
+impl<T: Terminal> fmt::Display for Event<T> {
+    /// The `fmt` function formats the event on one line, indented by its depth.
+    ///
+    /// Arguments:
+    ///
+    /// * `f`: A mutable reference to a `fmt::Formatter` object. This object is used for formatting and
+    ///   writing output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the events inside a rule are indented under the rule
+        let indent = match self {
+            Event::Try { .. } | Event::Backtrack { .. } | Event::Accept { .. } => self.depth() * 2,
+            _ => self.depth() * 2 + 2,
+        };
+        write!(f, "{}", " ".repeat(indent))?;
+
+        match self {
+            Event::Try { name, index, .. } => write!(f, "try {} #{}", name, index),
+            Event::Consume { lexeme, span, .. } => write!(
+                f,
+                "consume {} at {}..{}",
+                Self::describe(lexeme),
+                span.start,
+                span.end
+            ),
+            Event::Mismatch {
+                lexeme,
+                span,
+                expected,
+                ..
+            } => match expected {
+                Some(expected) => write!(
+                    f,
+                    "mismatch {} at {}..{}, expected {}",
+                    Self::describe(lexeme),
+                    span.start,
+                    span.end,
+                    expected.name()
+                ),
+                None => write!(
+                    f,
+                    "mismatch {} at {}..{}, expected the end of the rule",
+                    Self::describe(lexeme),
+                    span.start,
+                    span.end
+                ),
+            },
+            Event::Enter { name, .. } => write!(f, "enter {}", name),
+            Event::Check { name, holds, .. } => match holds {
+                true => write!(f, "check {} holds", name),
+                false => write!(f, "check {} fails", name),
+            },
+            Event::Backtrack { name, index, .. } => write!(f, "backtrack {} #{}", name, index),
+            Event::Accept { name, index, .. } => write!(f, "accept {} #{}", name, index),
+        }
+    }
+}
+
+/// The `Derivation` struct represents a rule matched by the `Parser` while accepting the input, with
+/// the lexemes matched by its terminals.
+///
+/// Properties:
+///
+/// * `name`: The name of the grammar set of the rule.
+/// * `index`: The index of the rule in its grammar set.
+/// * `lexemes`: The lexeme matched by each terminal of the rule, in order, `None` being the end of
+///   the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Derivation<T = Token> {
+    pub name: String,
+    pub index: usize,
+    pub lexemes: Vec<Option<Lexeme<T>>>,
+}
+
+/// The `Tracer` type is a function called by the `Parser` with each step of the analysis.
+pub type Tracer<'t, T = Token> = Box<dyn FnMut(&Event<T>) + 't>;
+
+/// The `Cursor` struct reads the tokens of the `Parser` and keeps the span of the last one, like a
+/// logos `Lexer` does.
+///
+/// Properties:
+///
+/// * `tokens`: The lexemes left to read.
+/// * `span`: The span of the last token read, or the empty span after it at the end of the input.
+#[derive(Clone)]
+struct Cursor<I> {
+    tokens: I,
+    span: Range<usize>,
+}
+
+impl<T, I: Iterator<Item = Lexeme<T>>> Cursor<I> {
+    /// The function `next` reads the next token.
+    ///
+    /// Returns:
+    ///
+    /// The lexeme of the token, whose token is `None` for an invalid one, or `None` at the end of the
+    /// input.
+    fn next(&mut self) -> Option<Lexeme<T>> {
+        match self.tokens.next() {
+            Some(lexeme) => {
+                self.span = lexeme.span.clone();
+                Some(lexeme)
+            }
+            None => {
+                self.span = self.span.end..self.span.end;
+                None
+            }
         }
     }
 }
 
 /// The `Parser` struct is used for parsing code and contains a lexer and a store.
 ///
+/// It analyzes the `Token`s of the crate by default, but can analyze any other `Terminal` given by
+/// a cloneable iterator over the tokens and their byte span, `None` being an invalid token (see
+/// `Tokens` for logos lexers and `indexed` for any sequence of tokens).
+///
 /// Properties:
 ///
-/// * `lexer`: The `lexer` property is an instance of the `Lexer` struct. It is a lexer that takes a
-/// static lifetime reference to a `Token` type. A lexer is responsible for breaking down a stream of
-/// characters into a sequence of tokens, which can then be processed by the parser.
+/// * `tokens`: The `tokens` property is the source of the tokens, usually a lexer. A lexer is
+///   responsible for breaking down a stream of characters into a sequence of tokens, which can then be
+///   processed by the parser.
 /// * `store`: The `store` property is an instance of the `Store` struct. It is used to store and manage
-/// data during the parsing process.
-pub struct Parser<'a> {
-    lexer: Lexer<'a, Token>,
-    store: Store,
+///   data during the parsing process.
+/// * `tracer`: The `tracer` property is an optional function called with each step of the analysis.
+/// * `abort`: The `abort` property is an optional flag which stops the analysis once it is set, e.g.
+///   by the tracer. The input is then not accepted.
+/// * `derivation`: The `derivation` property is the list of the rules being matched, in the order of
+///   the input. Once the input is accepted, it holds the rules which matched it.
+pub struct Parser<'a, T = Token, I = Tokens<'a, Token>> {
+    tokens: I,
+    store: Store<T>,
+    tracer: Option<Tracer<'a, T>>,
+    abort: Option<Arc<AtomicBool>>,
+    derivation: Vec<Derivation<T>>,
 }
 
 impl<'a> Parser<'a> {
-    /// The function `new` creates a new instance of the `Parser` struct with an empty lexer.
+    /// The function `new` creates a new instance of the `Parser` struct over a lexer of the crate's
+    /// `Token`s.
     ///
     /// Arguments:
     ///
-    /// * `input`: The `input` parameter is a reference to a static string (`&'static str`). It represents
-    /// the input that the parser will be working with.
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+    /// * `lexer`: The `lexer` parameter is the lexer of the input that the parser will be working with.
     ///
     /// Returns:
     ///
     /// A new instance of the `Parser` struct is being returned.
-    pub fn new(store: Store, lexer: Lexer<'a, Token>) -> Parser {
-        Parser { lexer, store }
+    pub fn new(store: Store, lexer: Lexer<'a, Token>) -> Parser<'a> {
+        Parser::from_tokens(store, Tokens::new(lexer))
     }
+}
 
-    /// The `parse` function retrieves all rules from the store, creates a rule set, and processes it using
-    /// the lexer.
+impl<'a, T, I> Parser<'a, T, I>
+where
+    T: Terminal,
+    I: Iterator<Item = Lexeme<T>> + Clone,
+{
+    /// The function `from_tokens` creates a new instance of the `Parser` struct over any source of
+    /// tokens.
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar, built for
+    ///   the same type of tokens.
+    /// * `tokens`: The `tokens` parameter is the source of the tokens with their byte span.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Parser` struct is being returned.
+    pub fn from_tokens(store: Store<T>, tokens: I) -> Parser<'a, T, I> {
+        Parser {
+            tokens,
+            store,
+            tracer: None,
+            abort: None,
+            derivation: Vec::new(),
+        }
+    }
+
+    /// The function `with_tracer` sets the function called with each step of the analysis.
+    ///
+    /// Arguments:
+    ///
+    /// * `tracer`: The `tracer` parameter is the function called with each `Event`.
+    ///
+    /// Returns:
+    ///
+    /// The `Parser` with the given tracer.
+    pub fn with_tracer(mut self, tracer: Tracer<'a, T>) -> Parser<'a, T, I> {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// The function `with_abort` sets the flag which stops the analysis once it is set.
+    ///
+    /// Arguments:
+    ///
+    /// * `abort`: The `abort` parameter is the flag, shared with whoever stops the analysis.
+    ///
+    /// Returns:
+    ///
+    /// The `Parser` with the given flag.
+    pub fn with_abort(mut self, abort: Arc<AtomicBool>) -> Parser<'a, T, I> {
+        self.abort = Some(abort);
+        self
+    }
+
+    /// The function `aborted` tells whether the analysis was stopped by the abort flag, in which
+    /// case `parse` returned false without a verdict on the input.
+    pub fn aborted(&self) -> bool {
+        self.abort
+            .as_ref()
+            .is_some_and(|abort| abort.load(Ordering::Relaxed))
+    }
+
+    /// The `parse` function retrieves the rules of the start set from the store and processes them
+    /// using the lexer, so that the derivation always begins at the start set.
     ///
     /// Returns:
     ///
     /// The `parse` function is returning a boolean value.
     pub fn parse(&mut self) -> bool {
-        // get all rules from the store and process them
-        let rules = self.store.get_all_rules();
-        Self::process_rule_set(&mut self.lexer, rules, 0, None, false)
+        self.derivation.clear();
+
+        // get the rules of the start set and process them
+        let rules = self.store.get_start_rules();
+        let mut cursor = Cursor {
+            tokens: self.tokens.clone(),
+            span: 0..0,
+        };
+        self.process_rule_set(&mut cursor, rules, 0, None, false, 0)
+    }
+
+    /// The function `derivation` returns the rules which matched the input, in the order of the
+    /// input, once `parse` accepted it.
+    pub fn derivation(&self) -> &[Derivation<T>] {
+        &self.derivation
+    }
+
+    /// The function `trace` reports an event to the tracer, if any.
+    ///
+    /// Arguments:
+    ///
+    /// * `event`: A function building the event, only called if there is a tracer.
+    fn trace(&mut self, event: impl FnOnce() -> Event<T>) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer(&event());
+        }
     }
 
     /// The function `process_rule_set` takes a lexer, a set of rules, an index, and a next token, and
@@ -97,46 +420,85 @@ impl<'a> Parser<'a> {
     ///
     /// Arguments:
     ///
-    /// * `_lexer`: A mutable reference to a `Lexer` object.
+    /// * `_lexer`: A mutable reference to the `Cursor` reading the tokens.
     /// * `rules`: A vector of Rule structs. Each Rule struct contains a set of steps to be processed.
     /// * `index`: The `index` parameter represents the current index in the input stream that the lexer
-    /// is processing. It is used to keep track of the progress of the lexer as it matches tokens
-    /// against the input.
-    /// * `next_token`: An optional parameter that represents the next token in the input stream. It is
-    /// used to determine if a rule matches based on the current token and the next token.
+    ///   is processing. It is used to keep track of the progress of the lexer as it matches tokens
+    ///   against the input.
+    /// * `next_token`: An optional parameter that represents the next token in the input stream, `None`
+    ///   inside being the end of the input. It is used to determine if a rule matches based on the
+    ///   current token and the next token.
     /// * `end`: A boolean value that indicates whether the lexer has reached the end of the input stream.
+    /// * `depth`: The number of rule sets entered before this one, reported to the tracer.
     ///
     /// Returns:
     ///
     /// The function `process_rule_set` returns a boolean value. It returns `true` if one of the rules
     /// in the `rules` vector matches, and `false` if none of the rules matches.
-    pub fn process_rule_set(
-        _lexer: &mut Lexer<'a, Token>,
-        rules: Vec<Rule>,
+    fn process_rule_set(
+        &mut self,
+        _lexer: &mut Cursor<I>,
+        rules: Vec<Rule<T>>,
         index: usize,
-        next_token: Option<Token>,
+        next_token: Option<Option<Lexeme<T>>>,
         end: bool,
+        depth: usize,
     ) -> bool {
         // try all rules one by one
         // if one of them matches, return true
         // if none of them matches, return false
-        for rule in rules {
+        for (position, rule) in rules.iter().enumerate() {
+            if self.aborted() {
+                return false;
+            }
+
             // clone the lexer because it is consumed after each call to next()
             let mut lexer = _lexer.clone();
 
+            self.trace(|| Event::Try {
+                name: rule.name.clone(),
+                index: rule.index,
+                remaining: rules[position + 1..]
+                    .iter()
+                    .map(|rule| (rule.name.clone(), rule.index))
+                    .collect(),
+                depth,
+            });
+
+            // the terminals matched by the rule are recorded until it is backtracked
+            let mark = self.derivation.len();
+            self.derivation.push(Derivation {
+                name: rule.name.clone(),
+                index: rule.index,
+                lexemes: Vec::new(),
+            });
+
             // process the rule (recursively)
-            let result = Self::process(
+            let result = self.process(
                 &mut lexer,
                 rule.steps.clone(),
                 index,
                 next_token.clone(),
                 end,
+                depth,
             );
 
             // if the rule matches, return true
             if result {
+                self.trace(|| Event::Accept {
+                    name: rule.name.clone(),
+                    index: rule.index,
+                    depth,
+                });
                 return true;
             }
+
+            self.derivation.truncate(mark);
+            self.trace(|| Event::Backtrack {
+                name: rule.name.clone(),
+                index: rule.index,
+                depth,
+            });
         }
 
         // if none of the rules matches, return false
@@ -148,30 +510,38 @@ impl<'a> Parser<'a> {
     ///
     /// Arguments:
     ///
-    /// * `lexer`: A mutable reference to a `Lexer` object, which is used to tokenize input.
+    /// * `lexer`: A mutable reference to the `Cursor` reading the tokens.
     /// * `steps`: `steps` is an `Arc<Mutex<Vec<RuleStep>>>` which represents a shared mutable reference to
-    /// a vector of `RuleStep` structs. The `Arc` type is used for reference counting and allows multiple
-    /// threads to have ownership of the same data. The `Mutex` type is used
+    ///   a vector of `RuleStep` structs. The `Arc` type is used for reference counting and allows multiple
+    ///   threads to have ownership of the same data. The `Mutex` type is used
     /// * `index`: The `index` parameter represents the current index of the step being processed in the
-    /// list of steps. It is used to keep track of the progress in the rule matching process.
-    /// * `next_token`: The `next_token` parameter is an optional `Token` that represents the next token to
-    /// be processed. It is used to pass the token from the previous step to the current step when the
-    /// current step is a reference to another rule. If `next_token` is `Some(token)`, it means
-    /// that the previous step was a reference to another rule, and the current step is a token.
+    ///   list of steps. It is used to keep track of the progress in the rule matching process.
+    /// * `next_token`: The `next_token` parameter is an optional token that represents the next token to
+    ///   be processed, `None` inside being the end of the input. It is used to pass the token from the
+    ///   previous step to the current step when the current step is a reference to another rule. If
+    ///   `next_token` is `Some(token)`, it means that the previous step was a reference to another
+    ///   rule, and the current step is a token.
     /// * `end`: The `end` parameter is a boolean value that indicates whether the lexer has reached the
-    /// end of the input stream.
+    ///   end of the input stream.
+    /// * `depth`: The number of rule sets entered before the one of the rule, reported to the tracer.
     ///
     /// Returns:
     ///
     /// The function `process` returns a `bool` which indicates whether the rule matching process
-    #[warn(clippy::only_used_in_recursion)]
-    pub fn process(
-        _lexer: &mut Lexer<'a, Token>,
-        steps: Arc<Mutex<Vec<RuleStep>>>,
+    fn process(
+        &mut self,
+        _lexer: &mut Cursor<I>,
+        steps: Arc<Mutex<Vec<RuleStep<T>>>>,
         index: usize,
-        next_token: Option<Token>,
+        next_token: Option<Option<Lexeme<T>>>,
         mut end: bool,
+        depth: usize,
     ) -> bool {
+        // the analysis was stopped => no rule can match
+        if self.aborted() {
+            return false;
+        }
+
         // clone the steps because we need to use it after we drop the lock
         let steps_cloned = steps.lock().unwrap().clone();
         drop(steps);
@@ -180,15 +550,18 @@ impl<'a> Parser<'a> {
         // this is mandatory because the lexer is consumed after each call to next()
         // and if we find on the previous call that the next step is a reference to another rule
         // so we didn't consume the token and lexer didn't not allow us to call previous()
+        // the end of the input is read once as a `None` token
         let token = match next_token {
-            Some(t) => Some(Ok(t)),
+            Some(t) => Some(t),
             None => match _lexer.next() {
-                Some(t) => Some(t),
+                // the input cannot be tokenized here => no rule can match
+                Some(lexeme) if lexeme.token.is_none() => return false,
+                Some(lexeme) => Some(Some(lexeme)),
                 None => match end {
                     true => None,
                     false => {
                         end = true;
-                        Some(Ok(Token::End))
+                        Some(None)
                     }
                 },
             },
@@ -198,40 +571,114 @@ impl<'a> Parser<'a> {
         let steps_size = steps_cloned.len();
 
         // no more tokens and no more steps => nothing to do
-        if (token.is_none() || token == Some(Ok(Token::End))) && index == steps_size {
+        if matches!(token, None | Some(None)) && index == steps_size {
             return true;
         }
 
+        // the span of the token, the end of the input for the end token
+        let span = _lexer.span.clone();
+
+        // a predicate reads the terminals matched by the rule, not the token, which is kept for the
+        // next step, even at the end of the input
+        if let Some(predicate) = steps_cloned
+            .get(index)
+            .and_then(|step| step.predicate.clone())
+        {
+            let values: Vec<Result<Value, String>> = self
+                .derivation
+                .last()
+                .map(|derivation| {
+                    derivation
+                        .lexemes
+                        .iter()
+                        .map(|lexeme| match lexeme {
+                            Some(lexeme) => Value::of(&lexeme.text, lexeme.value),
+                            None => Value::of("", None),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let holds = predicate.holds(&values);
+            let name = predicate.name.clone();
+            self.trace(|| Event::Check { name, holds, depth });
+
+            return holds
+                && self.process(
+                    _lexer,
+                    Arc::new(Mutex::new(steps_cloned)),
+                    index + 1,
+                    token,
+                    end,
+                    depth,
+                );
+        }
+
         // we still have tokens or steps => something is wrong
         if token.is_none() || index == steps_size {
+            if let Some(t) = token {
+                self.trace(|| Event::Mismatch {
+                    lexeme: t,
+                    span,
+                    expected: None,
+                    depth,
+                });
+            }
             return false;
         }
 
         // get the current step
-        let step: RuleStep = steps_cloned[index].clone();
+        let step: RuleStep<T> = steps_cloned[index].clone();
         match token {
             Some(t) => {
-                if step.token.is_none() && step.next.is_some() {
+                // the end of the input only matches the terminal of the end
+                let matches = match (&step.token, &t) {
+                    (Some(_), Some(t)) => t
+                        .token
+                        .as_ref()
+                        .is_some_and(|token| step.matches(token, Some(&t.text))),
+                    (Some(expected), None) => expected.is_end(),
+                    (None, _) => false,
+                };
+
+                if let (None, Some(temp)) = (&step.token, step.next) {
                     // trick to avoid consuming the lock
-                    let temp = step.next.unwrap();
                     let temp2 = temp.lock().unwrap();
+                    let name = temp2.name.clone();
                     let rules = temp2.clone().rules;
                     drop(temp2);
                     drop(temp);
 
                     // if the current step is a reference to another rules set
-                    Self::process_rule_set(_lexer, rules, 0, Some(t.unwrap()), end)
-                } else if t.unwrap() == step.token.unwrap() {
-                    // if the current step is a token
-                    Self::process(
+                    self.trace(|| Event::Enter { name, depth });
+                    self.process_rule_set(_lexer, rules, 0, Some(t), end, depth + 1)
+                } else if matches {
+                    // if the current step is a token, the rules are right-linear so the last
+                    // rule of the derivation is the one of the step
+                    if let Some(derivation) = self.derivation.last_mut() {
+                        derivation.lexemes.push(t.clone());
+                    }
+                    self.trace(|| Event::Consume {
+                        lexeme: t,
+                        span,
+                        depth,
+                    });
+                    self.process(
                         _lexer,
                         Arc::new(Mutex::new(steps_cloned)),
                         index + 1,
                         None,
                         end,
+                        depth,
                     )
                 } else {
                     // if the current step is a token and it doesn't match the current token
+                    self.trace(|| Event::Mismatch {
+                        lexeme: t,
+                        span,
+                        expected: step.token,
+                        depth,
+                    });
                     false
                 }
             }
@@ -239,3 +686,62 @@ impl<'a> Parser<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::{Error, GrammarErrorKind},
+        grammar::Grammar,
+    };
+
+    #[test]
+    fn lexes_the_rate_and_delay_keywords() {
+        let tokens: Vec<Option<Token>> = Token::lexer("rate delay rates Delay")
+            .map(Result::ok)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Some(Token::Rate),
+                Some(Token::Delay),
+                Some(Token::Identifier),
+                Some(Token::Identifier),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_the_tokens_by_their_name() {
+        for token in [
+            Token::Contact,
+            Token::Rate,
+            Token::Delay,
+            Token::Identifier,
+            Token::Number,
+            Token::End,
+        ] {
+            assert_eq!(Token::from_string(&format!("{:?}", token)), Some(token));
+        }
+
+        for name in ["Options", "rate", "RATE", ""] {
+            assert_eq!(Token::from_string(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_a_grammar_using_an_unknown_token() {
+        let result = Grammar::from_text("S -> options | rate delay")
+            .unwrap()
+            .compile();
+        assert!(
+            matches!(
+                &result,
+                Err(Error::Grammar { kind: GrammarErrorKind::UnknownTerminal(name), .. })
+                    if name == "Options"
+            ),
+            "{:?}",
+            result.err()
+        );
+    }
+}
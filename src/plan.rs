@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::{
+    lexer::{self, Lexeme},
+    parser::Token,
+};
+
+/// The `Check` enum represents the semantic checks of a contact plan.
+///
+/// Variants:
+///
+/// * `Order`: A contact, a rate window or a delay window does not end after it starts.
+/// * `SelfContact`: A contact goes from a node to itself.
+/// * `Overlap`: Two contacts from the same node to the same node overlap in time.
+/// * `Window`: A rate or a delay window is not inside the contact it follows.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    Order,
+    SelfContact,
+    Overlap,
+    Window,
+}
+
+impl Check {
+    /// The function `name` returns the name of the check, as printed and in the JSON report.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::Order => "order",
+            Check::SelfContact => "self-contact",
+            Check::Overlap => "overlap",
+            Check::Window => "window",
+        }
+    }
+}
+
+/// The `Finding` struct represents a line of a contact plan which is well-formed but inconsistent.
+///
+/// Properties:
+///
+/// * `line`: The line of the input, starting at 1.
+/// * `column`: The column of the first token of the line, starting at 1.
+/// * `span`: The byte span of the tokens of the line.
+/// * `check`: The check which failed.
+/// * `message`: The description of the inconsistency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub line: usize,
+    pub column: usize,
+    pub span: Range<usize>,
+    pub check: Check,
+    pub message: String,
+}
+
+/// The `Contact` struct represents a contact of the plan read so far.
+///
+/// Properties:
+///
+/// * `from`: The node sending during the contact.
+/// * `to`: The node receiving during the contact.
+/// * `start`: The time the contact starts.
+/// * `end`: The time the contact ends.
+/// * `line`: The line of the contact in the input.
+struct Contact {
+    from: String,
+    to: String,
+    start: u64,
+    end: u64,
+    line: usize,
+}
+
+/// The function `check` checks the consistency of a contact plan, made of `contact FROM TO START END`
+/// lines each followed by its `rate START END RATE` and `delay START END DELAY` lines, as in the
+/// examples.
+///
+/// Each line is read on its own from its tokens, so the lines of another shape are left to the
+/// grammar and skipped. The contacts are directed: `contact A B` and `contact B A` never overlap.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the contact plan.
+///
+/// Returns:
+///
+/// The findings of the plan, in the order of its lines, empty if it is consistent.
+pub fn check(input: &str) -> Vec<Finding> {
+    let lexemes = lexer::lex(input);
+    let mut findings = Vec::new();
+    let mut contacts: Vec<Contact> = Vec::new();
+    // the contact the rate and delay lines belong to, unless it does not end after it starts
+    let mut current: Option<usize> = None;
+
+    for line in lexemes.chunk_by(|a, b| a.line == b.line) {
+        let (first, last) = (&line[0], &line[line.len() - 1]);
+        let mut finding = |check, message| {
+            findings.push(Finding {
+                line: first.line,
+                column: first.column,
+                span: first.span.start..last.span.end,
+                check,
+                message,
+            })
+        };
+
+        match line {
+            [keyword, from, to, start, end] if is(keyword, Token::Contact) => {
+                let (Some(start), Some(end)) = (number(start), number(end)) else {
+                    continue;
+                };
+                if !is(from, Token::Identifier) || !is(to, Token::Identifier) {
+                    continue;
+                }
+                current = None;
+
+                if from.text == to.text {
+                    finding(
+                        Check::SelfContact,
+                        format!("the contact goes from `{}` to itself", from.text),
+                    );
+                }
+                if start >= end {
+                    finding(
+                        Check::Order,
+                        format!(
+                            "the contact ends at {}, not after it starts at {}",
+                            end, start
+                        ),
+                    );
+                    continue;
+                }
+
+                for other in contacts.iter().filter(|other| {
+                    other.from == from.text
+                        && other.to == to.text
+                        && other.start < end
+                        && start < other.end
+                }) {
+                    finding(
+                        Check::Overlap,
+                        format!(
+                            "the contact {}..{} overlaps the contact from `{}` to `{}` of line {} ({}..{})",
+                            start, end, other.from, other.to, other.line, other.start, other.end
+                        ),
+                    );
+                }
+
+                current = Some(contacts.len());
+                contacts.push(Contact {
+                    from: from.text.clone(),
+                    to: to.text.clone(),
+                    start,
+                    end,
+                    line: first.line,
+                });
+            }
+            [keyword, start, end, amount]
+                if is(keyword, Token::Rate) || is(keyword, Token::Delay) =>
+            {
+                let (Some(start), Some(end), Some(_)) =
+                    (number(start), number(end), number(amount))
+                else {
+                    continue;
+                };
+
+                if start >= end {
+                    finding(
+                        Check::Order,
+                        format!(
+                            "the {} window ends at {}, not after it starts at {}",
+                            keyword.text, end, start
+                        ),
+                    );
+                    continue;
+                }
+
+                if let Some(contact) = current.map(|index| &contacts[index]) {
+                    if start < contact.start || end > contact.end {
+                        finding(
+                            Check::Window,
+                            format!(
+                                "the {} window {}..{} is not inside its contact {}..{} of line {}",
+                                keyword.text, start, end, contact.start, contact.end, contact.line
+                            ),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// The function `is` returns true if the lexeme is a token of the given kind.
+fn is(lexeme: &Lexeme, token: Token) -> bool {
+    lexeme.token == Some(token)
+}
+
+/// The function `number` returns the value of a lexeme if it is a number.
+fn number(lexeme: &Lexeme) -> Option<u64> {
+    match is(lexeme, Token::Number) {
+        true => lexeme.value,
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(input: &str) -> Vec<(usize, Check)> {
+        check(input)
+            .into_iter()
+            .map(|finding| (finding.line, finding.check))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_consistent_plan() {
+        let input = "contact A B 10 20\nrate 10 15 3\ndelay 15 20 1\ncontact B A 10 20\n\
+                     contact A B 20 30\n";
+        assert!(check(input).is_empty());
+    }
+
+    #[test]
+    fn reports_the_findings_of_each_check() {
+        let input = "contact A A 10 20\ncontact A B 20 10\nrate 5 1 3\ncontact A B 0 50\n\
+                     rate 40 60 3\ncontact A B 30 40\n";
+
+        assert_eq!(
+            checks(input),
+            vec![
+                (1, Check::SelfContact),
+                (2, Check::Order),
+                (3, Check::Order),
+                (5, Check::Window),
+                (6, Check::Overlap),
+            ]
+        );
+    }
+
+    #[test]
+    fn locates_the_findings() {
+        let findings = check("contact A B 10 20\n  rate 30 40 1\n");
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            (
+                findings[0].line,
+                findings[0].column,
+                findings[0].span.clone()
+            ),
+            (2, 3, 20..32)
+        );
+        assert_eq!(
+            findings[0].message,
+            "the rate window 30..40 is not inside its contact 10..20 of line 1"
+        );
+    }
+
+    #[test]
+    fn skips_the_lines_of_another_shape() {
+        assert!(check("contact A B 20\nrate 5 1\nnode A\n").is_empty());
+    }
+}
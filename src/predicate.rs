@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::{
+    capture,
+    expression::{Expression, Reference, Scope, Value},
+    grammar::GrammarRule,
+};
+
+/// The `Predicate` struct represents a condition on the captures of a rule, written `name: condition`
+/// in the grammar, e.g. `ordered: start < end`. The rule only matches when the condition holds, and
+/// the name tells which condition rejected an input.
+///
+/// Properties:
+///
+/// * `name`: The name of the predicate, used in the errors.
+/// * `expression`: The condition, reading the captures of the rule by their name.
+/// * `captures`: The name and the index in the terminals of the rule of each capture.
+/// * `set`: The name of the grammar set of the rule.
+/// * `rule`: The index of the rule in its grammar set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    pub name: String,
+    pub expression: Expression,
+    pub captures: Vec<(String, usize)>,
+    pub set: String,
+    pub rule: usize,
+}
+
+impl Predicate {
+    /// The function `compile` reads a predicate of a rule and checks that it only reads the
+    /// captures of the rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the predicate as written in the grammar.
+    /// * `rule`: The `rule` parameter is the rule of the grammar the predicate belongs to.
+    /// * `set`: The `set` parameter is the name of the grammar set of the rule.
+    /// * `index`: The `index` parameter is the index of the rule in its grammar set.
+    ///
+    /// Returns:
+    ///
+    /// The `Predicate`, or a message describing why the text is not a valid predicate.
+    pub fn compile(
+        text: &str,
+        rule: &GrammarRule,
+        set: &str,
+        index: usize,
+    ) -> Result<Predicate, String> {
+        let (name, condition) = text
+            .split_once(':')
+            .ok_or_else(|| "expected `name: condition`".to_string())?;
+
+        let name = name.trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!("invalid predicate name `{}`", name));
+        }
+
+        let expression = Expression::parse(condition)?;
+        let captures: Vec<(String, usize)> = rule
+            .terminals
+            .iter()
+            .enumerate()
+            .filter_map(|(position, terminal)| {
+                Some((capture::split(terminal).0?.to_string(), position))
+            })
+            .collect();
+
+        // the rule is matched before the rules around it, only its captures are known
+        for reference in expression.references() {
+            if reference.scope != Scope::Own {
+                return Err(format!("`{}` cannot be read by a predicate", reference));
+            }
+            if !captures.iter().any(|(name, _)| *name == reference.name) {
+                return Err(format!("unknown capture `{}`", reference.name));
+            }
+        }
+
+        Ok(Predicate {
+            name: name.to_string(),
+            expression,
+            captures,
+            set: set.to_string(),
+            rule: index,
+        })
+    }
+
+    /// The function `holds` evaluates the condition of the predicate.
+    ///
+    /// Arguments:
+    ///
+    /// * `values`: The `values` parameter is the value of each terminal matched by the rule so far,
+    ///   in order, or the message telling why it cannot be read (see `Value::of`).
+    ///
+    /// Returns:
+    ///
+    /// True if the condition is `true`. A condition which cannot be computed, e.g. a division by
+    /// zero or a number too large, or which is not a boolean does not hold.
+    pub fn holds(&self, values: &[Result<Value, String>]) -> bool {
+        let mut resolve = |reference: &Reference| {
+            self.captures
+                .iter()
+                .find(|(name, _)| *name == reference.name)
+                .and_then(|(_, position)| values.get(*position).cloned())
+                .unwrap_or_else(|| Err(format!("unknown capture `{}`", reference.name)))
+        };
+
+        self.expression.evaluate(&mut resolve) == Ok(Value::Bool(true))
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, grammar::Grammar, report};
+
+    const GRAMMAR: &str = "S -> C\nC -> contact from:identifier to:identifier start:number \
+                           end:number C { require ordered: start < end; require distinct: from \
+                           != to } | None";
+
+    fn rule() -> GrammarRule {
+        Grammar::from_text(GRAMMAR).unwrap().sets[1].rules[0].clone()
+    }
+
+    #[test]
+    fn compiles_predicates_over_captures() {
+        let predicate = Predicate::compile("ordered: start < end", &rule(), "C", 0).unwrap();
+        assert_eq!(predicate.to_string(), "ordered: (start < end)");
+        assert_eq!(predicate.captures[2], ("start".to_string(), 3));
+
+        assert!(Predicate::compile("start < end", &rule(), "C", 0).is_err());
+        assert!(Predicate::compile("late: start < stop", &rule(), "C", 0).is_err());
+        assert!(Predicate::compile("inherited: parent.end > 0", &rule(), "C", 0).is_err());
+    }
+
+    #[test]
+    fn holds_over_the_values_of_the_terminals() {
+        let predicate = Predicate::compile("ordered: start < end", &rule(), "C", 0).unwrap();
+        let values = |start: &str, end: &str| {
+            ["contact", "A", "B", start, end]
+                .iter()
+                .map(|text| Value::of(text, text.parse().ok()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(predicate.holds(&values("10", "20")));
+        assert!(!predicate.holds(&values("20", "10")));
+        // a value which cannot be read does not hold
+        assert!(!predicate.holds(&values("10", "18446744073709551615")));
+    }
+
+    #[test]
+    fn reports_the_predicate_which_does_not_hold() {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+
+        let report = report::validate(&grammar, "contact A B 10 20\ncontact B C 30 5\n").unwrap();
+        assert!(
+            matches!(&report.errors[..], [Error::Predicate { span, set, rule: 0, name }]
+                if *span == (33..34) && set == "C" && name == "ordered"),
+            "{:?}",
+            report.errors
+        );
+
+        let report = report::validate(&grammar, "contact A A 10 20\n").unwrap();
+        assert!(
+            matches!(&report.errors[..], [Error::Predicate { name, .. }] if name == "distinct")
+        );
+    }
+}
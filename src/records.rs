@@ -0,0 +1,121 @@
+use crate::{error::Error, store::Store, validator::Validator};
+
+/// The `RecordLine` struct represents the result of the validation of one line of the input.
+///
+/// Properties:
+///
+/// * `number`: The number of the line in the input, starting at 1.
+/// * `text`: The text of the line.
+/// * `error`: The error which rejected the line, if any. Its offsets are relative to the beginning
+///   of the line.
+#[derive(Debug, Clone)]
+pub struct RecordLine {
+    pub number: usize,
+    pub text: String,
+    pub error: Option<Error>,
+}
+
+/// The `RecordReport` struct represents the result of the validation of an input record by record.
+///
+/// Properties:
+///
+/// * `lines`: The results of the non-empty lines of the input, in order.
+/// * `structure`: The result of the validation of the sequence of records against the whole
+///   grammar, if it was requested. On failure, it contains the number of the line where the
+///   sequence stopped being valid and the error. The lines rejected on their own are ignored by
+///   this validation.
+#[derive(Debug, Clone)]
+pub struct RecordReport {
+    pub lines: Vec<RecordLine>,
+    pub structure: Option<Result<(), (usize, Error)>>,
+}
+
+impl RecordReport {
+    /// The function `is_correct` returns true if every line is a valid record and, when it was
+    /// checked, if the sequence of records is valid.
+    pub fn is_correct(&self) -> bool {
+        self.lines.iter().all(|line| line.error.is_none())
+            && !matches!(self.structure, Some(Err(_)))
+    }
+}
+
+/// The function `validate_records` validates each line of the input on its own against the rule set
+/// used as the record rule and, optionally, the sequence of records against the whole grammar.
+///
+/// Arguments:
+///
+/// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+/// * `record`: The `record` parameter is the name of the rule set each line must match.
+/// * `input`: The `input` parameter is the input to validate.
+/// * `structure`: The `structure` parameter tells whether the sequence of records must be validated.
+///
+/// Returns:
+///
+/// a `RecordReport`, or an error if the store has no rule set named `record`.
+pub fn validate_records(
+    store: &Store,
+    record: &str,
+    input: &str,
+    structure: bool,
+) -> Result<RecordReport, Error> {
+    let mut lines = Vec::new();
+
+    // the text given to the structure validation, where the rejected lines are blanked
+    // so that the offsets still match the input
+    let mut sequence = String::with_capacity(input.len());
+
+    for (index, text) in input.split('\n').enumerate() {
+        if index > 0 {
+            sequence.push('\n');
+        }
+
+        if text.trim().is_empty() {
+            sequence.push_str(text);
+            continue;
+        }
+
+        let mut validator = Validator::from_rule_set(store, record)?;
+        let error = validator.feed(text).and_then(|_| validator.finish()).err();
+
+        match error {
+            Some(_) => sequence.push_str(" ".repeat(text.len()).as_str()),
+            None => sequence.push_str(text),
+        }
+
+        lines.push(RecordLine {
+            number: index + 1,
+            text: text.trim_end().to_string(),
+            error,
+        });
+    }
+
+    let structure = match structure {
+        true => Some(validate_structure(store, sequence.as_str())),
+        false => None,
+    };
+
+    Ok(RecordReport { lines, structure })
+}
+
+/// The function `validate_structure` validates the given input against the whole grammar.
+///
+/// Arguments:
+///
+/// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+/// * `input`: The `input` parameter is the input to validate.
+///
+/// Returns:
+///
+/// `Ok(())` if the input is accepted, or the number of the line where it was rejected and the error
+/// otherwise.
+fn validate_structure(store: &Store, input: &str) -> Result<(), (usize, Error)> {
+    let mut validator = Validator::new(store);
+
+    validator
+        .feed(input)
+        .and_then(|_| validator.finish())
+        .map_err(|error| {
+            let line = input[..error.offset()].matches('\n').count() + 1;
+            (line, error)
+        })
+}
@@ -0,0 +1,276 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    attribute::{self, Attributes},
+    error::Error,
+    grammar::CompiledGrammar,
+    parser::{Derivation, Event, Tracer},
+    symbol,
+    validator::Validator,
+};
+
+/// The `Report` struct represents the result of the validation of an input.
+///
+/// Properties:
+///
+/// * `accepted`: True if the input is accepted by the grammar.
+/// * `errors`: The errors found in the input, in order, empty if the input is accepted. After each
+///   error, the analysis resumes on the next synchronization token of the grammar. An input matching
+///   the grammar is still rejected by its undeclared and redeclared identifiers (see
+///   `symbol::check`), or by an attribute which cannot be computed.
+/// * `attributes`: The attributes of the rules which matched the input (see `attribute::evaluate`),
+///   empty if the input is rejected.
+/// * `derivation`: The rules which matched the input (see `Parser::derivation`), empty if the input
+///   is rejected, e.g. to translate it without parsing it again (see `template::translate`).
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub accepted: bool,
+    pub errors: Vec<Error>,
+    pub attributes: Vec<Attributes>,
+    pub derivation: Vec<Derivation>,
+}
+
+/// The function `validate` checks whether the input is accepted by the grammar and, if it is not,
+/// reports every error of the input.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the `CompiledGrammar` to validate the input with.
+/// * `input`: The `input` parameter is the input to validate.
+///
+/// Returns:
+///
+/// a `Report` of the validation.
+pub fn validate(grammar: &CompiledGrammar, input: &str) -> Result<Report, Error> {
+    validate_with_tracer(grammar, input, None)
+}
+
+/// The function `validate_with_tracer` is the same as `validate`, reporting each step of the
+/// analysis to the given tracer.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the `CompiledGrammar` to validate the input with.
+/// * `input`: The `input` parameter is the input to validate.
+/// * `tracer`: The `tracer` parameter is the function called with each step of the analysis, if any.
+///
+/// Returns:
+///
+/// a `Report` of the validation.
+pub fn validate_with_tracer<'a>(
+    grammar: &CompiledGrammar,
+    input: &'a str,
+    tracer: Option<Tracer<'a>>,
+) -> Result<Report, Error> {
+    // the furthest token the parser could not match locates the error if the validator finds none
+    let furthest: Rc<RefCell<Option<Error>>> = Rc::default();
+    let mismatches = furthest.clone();
+    let mut tracer = tracer;
+    let mut parser = grammar.parser(input).with_tracer(Box::new(move |event| {
+        record_mismatch(&mut mismatches.borrow_mut(), event);
+        if let Some(tracer) = tracer.as_mut() {
+            tracer(event);
+        }
+    }));
+
+    if parser.parse() {
+        // an accepted input is rejected if its identifiers are inconsistent
+        let errors = symbol::check(grammar.grammar(), parser.derivation());
+        if !errors.is_empty() {
+            return Ok(Report {
+                accepted: false,
+                errors,
+                attributes: Vec::new(),
+                derivation: Vec::new(),
+            });
+        }
+
+        // or if its attributes cannot be computed
+        return Ok(
+            match attribute::evaluate(grammar.grammar(), parser.derivation()) {
+                Ok(attributes) => Report {
+                    accepted: true,
+                    errors: Vec::new(),
+                    attributes,
+                    derivation: parser.derivation().to_vec(),
+                },
+                Err(error) => Report {
+                    accepted: false,
+                    errors: vec![error],
+                    attributes: Vec::new(),
+                    derivation: Vec::new(),
+                },
+            },
+        );
+    }
+
+    // explain where the input stopped being a prefix of an accepted sentence
+    let store = grammar.store();
+    let mut validator = Validator::new(store).with_recovery(store.sync.clone());
+    let _ = validator.feed(input);
+
+    let mut errors = validator.finish_all();
+    if errors.is_empty() {
+        // the input is rejected without error, which is still reported
+        let end = input.len();
+        errors.push(furthest.take().unwrap_or(Error::Syntax {
+            span: end..end,
+            found: None,
+            expected: Vec::new(),
+        }));
+    }
+
+    Ok(Report {
+        accepted: false,
+        errors,
+        attributes: Vec::new(),
+        derivation: Vec::new(),
+    })
+}
+
+/// The function `record_mismatch` keeps the `Syntax` error of the furthest token the parser could
+/// not match, with every token expected there.
+///
+/// Arguments:
+///
+/// * `furthest`: The error of the furthest mismatch so far, if any.
+/// * `event`: The step of the analysis made by the parser.
+fn record_mismatch(furthest: &mut Option<Error>, event: &Event) {
+    let Event::Mismatch {
+        lexeme,
+        span,
+        expected,
+        ..
+    } = event
+    else {
+        return;
+    };
+
+    match furthest {
+        Some(Error::Syntax {
+            span: current,
+            expected: tokens,
+            ..
+        }) if current.start == span.start => {
+            if let Some(token) = expected.as_ref().filter(|token| !tokens.contains(token)) {
+                tokens.push(token.clone());
+            }
+        }
+        Some(Error::Syntax { span: current, .. }) if current.start > span.start => {}
+        _ => {
+            *furthest = Some(Error::Syntax {
+                span: span.clone(),
+                found: lexeme.as_ref().and_then(|lexeme| lexeme.token.clone()),
+                expected: expected.iter().cloned().collect(),
+            })
+        }
+    }
+}
+
+/// The function `location` converts a byte offset of the input into a line and a column, the column
+/// counting characters rather than bytes.
+///
+/// Arguments:
+///
+/// * `input`: The `input` parameter is the analyzed input.
+/// * `offset`: The `offset` parameter is a byte offset in the input. An offset past the end of the
+///   input is located at its end, and one inside a character at the start of the character.
+///
+/// Returns:
+///
+/// The line and the column of the offset, both starting at 1.
+pub fn location(input: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |index| index + 1)..]
+        .chars()
+        .count()
+        + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grammar::Grammar, parser::Token, template};
+
+    fn mismatch(start: usize, expected: Option<Token>) -> Event {
+        Event::Mismatch {
+            lexeme: None,
+            span: start..start + 1,
+            expected,
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_the_furthest_mismatch() {
+        let mut furthest = None;
+        record_mismatch(&mut furthest, &mismatch(4, Some(Token::Rate)));
+        record_mismatch(&mut furthest, &mismatch(8, Some(Token::Number)));
+        record_mismatch(&mut furthest, &mismatch(2, Some(Token::Delay)));
+        record_mismatch(&mut furthest, &mismatch(8, Some(Token::Identifier)));
+        record_mismatch(&mut furthest, &mismatch(8, None));
+
+        assert!(
+            matches!(&furthest, Some(Error::Syntax { span, expected, .. })
+                if *span == (8..9) && *expected == vec![Token::Number, Token::Identifier]),
+            "{:?}",
+            furthest
+        );
+    }
+
+    #[test]
+    fn reports_every_error_of_a_rejected_input() {
+        let grammar =
+            Grammar::from_text("S -> C\nC -> contact identifier number C | None\nsync: contact")
+                .unwrap()
+                .compile()
+                .unwrap();
+
+        let report = validate(&grammar, "contact a 1\ncontact 2\ncontact b c\n").unwrap();
+        assert!(!report.accepted);
+        assert_eq!(report.errors.len(), 2, "{:?}", report.errors);
+
+        let report = validate(&grammar, "contact a 1\n").unwrap();
+        assert!(report.accepted && report.errors.is_empty());
+    }
+
+    #[test]
+    fn keeps_the_derivation_of_an_accepted_input() {
+        let grammar = Grammar::from_text(
+            "S -> C { emit \"begin\" }\nC -> contact from:identifier C { emit \"${from}\" } | None",
+        )
+        .unwrap()
+        .compile()
+        .unwrap();
+
+        let report = validate(&grammar, "contact a contact b").unwrap();
+        let names: Vec<&str> = report.derivation.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["S", "C", "C", "C"]);
+        assert_eq!(
+            template::translate(grammar.grammar(), &report.derivation).unwrap(),
+            grammar.translate("contact a contact b").unwrap().unwrap()
+        );
+
+        assert!(validate(&grammar, "contact").unwrap().derivation.is_empty());
+    }
+
+    #[test]
+    fn locates_the_offsets_in_characters() {
+        assert_eq!(location("ab\ncé d", 6), (2, 3));
+        assert_eq!(location("ab\ncé d", 3), (2, 1));
+        assert_eq!(location("", 0), (1, 1));
+
+        // an offset past the end or inside a character does not panic
+        assert_eq!(location("", 12), (1, 1));
+        assert_eq!(location("ab\ncé d", 40), (2, 5));
+        assert_eq!(location("ab\ncé d", 5), (2, 2));
+    }
+}
@@ -3,26 +3,33 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use crate::parser::Token;
+use crate::{constraint::Constraint, parser::Token, predicate::Predicate, terminal::Terminal};
 
 /// The `RuleSet` struct represents a collection of rules.
 ///
 /// Properties:
 ///
+/// * `name`: The `name` property is the name of the grammar set the rules come from.
 /// * `rules`: The `rules` property is a vector (dynamic array) of `Rule` structs.
 #[derive(Debug, Clone)]
-pub struct RuleSet {
-    pub rules: Vec<Rule>,
+pub struct RuleSet<T = Token> {
+    pub name: String,
+    pub rules: Vec<Rule<T>>,
 }
 
-impl RuleSet {
-    /// The function `new` creates a new instance of the `RuleSet` struct with an empty vector of rules.
+impl<T> RuleSet<T> {
+    /// The function `new` creates a new instance of the `RuleSet` struct with the given rules.
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: The `name` parameter is the name of the grammar set the rules come from.
+    /// * `rules`: The `rules` parameter is the vector of rules of the set.
     ///
     /// Returns:
     ///
     /// A new instance of the `RuleSet` struct is being returned.
-    pub fn new(rules: Vec<Rule>) -> RuleSet {
-        RuleSet { rules }
+    pub fn new(name: String, rules: Vec<Rule<T>>) -> RuleSet<T> {
+        RuleSet { name, rules }
     }
 }
 
@@ -30,15 +37,19 @@ impl RuleSet {
 ///
 /// Properties:
 ///
+/// * `name`: The `name` property is the name of the grammar set the rule comes from.
+/// * `index`: The `index` property is the index of the rule in its grammar set.
 /// * `steps`: The `steps` property is a vector of `RuleStep` structs, wrapped in an `Arc` and a
-/// `Mutex`.
+///   `Mutex`.
 #[derive(Debug, Clone)]
-pub struct Rule {
+pub struct Rule<T = Token> {
+    pub name: String,
+    pub index: usize,
     // We use Arc and Mutex to allow passing to a next step a reference to the another rules
     // instead of a copy of the rules and avoid infinite recursion when we have a loop in the rules
     // definition (e.g. rule 1 -> rule 2 -> rule 3 -> rule 1)
     // This is specific to Rust and is (probably) not needed in other languages
-    pub steps: Arc<Mutex<Vec<RuleStep>>>,
+    pub steps: Arc<Mutex<Vec<RuleStep<T>>>>,
 }
 
 /// The `RuleStep` struct represents a step in a rule, with an optional token and an optional next rule set.
@@ -46,24 +57,66 @@ pub struct Rule {
 /// Properties:
 ///
 /// * `token`: The `token` property is an optional field that represents a token. Tokens are typically
-/// used in parsing and lexical analysis to represent the smallest units of a programming language, such
-/// as keywords, identifiers, operators, and literals. In this case, the `token` field is of type
-/// `Option<Token>
+///   used in parsing and lexical analysis to represent the smallest units of a programming language, such
+///   as keywords, identifiers, operators, and literals. In this case, the `token` field is of type
+///   `Option<T>`, where `T` is the `Token` of the crate or any other `Terminal`.
+/// * `constraint`: The `constraint` property is an optional condition on the text of the token, e.g.
+///   the range of a number (see `Constraint`).
+/// * `predicate`: The `predicate` property is an optional condition on the terminals matched so far
+///   by the rule, checked without reading a token. A step with a predicate has no token and no next
+///   rule set.
 /// * `next`: The `next` property is an optional field that holds a reference to the next `RuleSet` in a
-/// sequence of rules. It is wrapped in a `Box` to allow for dynamic allocation and ownership transfer.
+///   sequence of rules. It is wrapped in a `Box` to allow for dynamic allocation and ownership transfer.
 #[derive(Clone)]
-pub struct RuleStep {
-    pub token: Option<Token>,
-    pub next: Option<Arc<Mutex<RuleSet>>>,
+pub struct RuleStep<T = Token> {
+    pub token: Option<T>,
+    pub constraint: Option<Constraint>,
+    pub predicate: Option<Predicate>,
+    pub next: Option<Arc<Mutex<RuleSet<T>>>>,
 }
 
-impl Debug for RuleStep {
+impl<T: Terminal> RuleStep<T> {
+    /// The function `matches` returns true if the step expects the given token and its constraint,
+    /// if any, is satisfied by the text of the token.
+    ///
+    /// Arguments:
+    ///
+    /// * `token`: The `token` parameter is the kind of the token read.
+    /// * `text`: The `text` parameter is the text of the token, or `None` to only compare the kinds.
+    ///
+    /// Returns:
+    ///
+    /// True if the step matches the token.
+    pub fn matches(&self, token: &T, text: Option<&str>) -> bool {
+        if self.token.as_ref() != Some(token) {
+            return false;
+        }
+
+        match (&self.constraint, text) {
+            (Some(constraint), Some(text)) => constraint.check(text, token.value(text)),
+            _ => true,
+        }
+    }
+
+    /// The function `describe` returns the terminal of the step as written in the grammar, with its
+    /// constraint, e.g. `Number[..100]`, or `None` if the step is a reference to another rule set.
+    pub fn describe(&self) -> Option<String> {
+        let token = self.token.as_ref()?;
+
+        Some(match &self.constraint {
+            Some(constraint) => format!("{}[{}]", token.name(), constraint),
+            None => token.name(),
+        })
+    }
+}
+
+impl<T: Debug> Debug for RuleStep<T> {
     /// The `fmt` function is used to format the `RuleStep` struct for debugging purposes in Rust.
     ///
     /// Arguments:
     ///
     /// * `f`: A mutable reference to a `std::fmt::Formatter` object. This object is used to format the
-    /// output.
+    ///   output.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.next.is_some() {
             return f
@@ -72,8 +125,16 @@ impl Debug for RuleStep {
                 .finish();
         }
 
+        if let Some(predicate) = &self.predicate {
+            return f
+                .debug_struct("RuleStep")
+                .field("predicate", &predicate.to_string())
+                .finish();
+        }
+
         f.debug_struct("RuleStep")
             .field("token", &self.token)
+            .field("constraint", &self.constraint)
             .finish()
     }
 }
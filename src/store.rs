@@ -1,23 +1,35 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
-use crate::rules::{Rule, RuleSet};
+use crate::{
+    parser::Token,
+    rules::{Rule, RuleSet},
+};
 
 /// The `Store` struct is a container for a shared mutable reference to a `RuleSet` object, wrapped in
 /// an `Arc` and `Mutex`.
 ///
 /// Properties:
 ///
-/// * `c`: `c` is a public field of type `Arc<Mutex<RuleSet>>`.
-#[derive(Debug, Clone, Default)]
-pub struct Store {
-    pub sets: HashMap<String, Arc<Mutex<RuleSet>>>,
+/// * `sets`: The rule sets and their names, in the order they were added. The first one is the start
+///   set, from which the analysis of an input begins.
+/// * `sync`: The tokens on which the analysis resynchronizes after an error.
+#[derive(Debug, Clone)]
+pub struct Store<T = Token> {
+    pub sets: Vec<(String, Arc<Mutex<RuleSet<T>>>)>,
+    pub sync: Vec<T>,
 }
 
-impl Store {
-    /// The `new` function creates a new instance of the `Store` struct with an empty `sets` HashMap.
+impl<T> Default for Store<T> {
+    fn default() -> Self {
+        Store {
+            sets: Vec::new(),
+            sync: Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> Store<T> {
+    /// The `new` function creates a new instance of the `Store` struct with no rule set.
     ///
     /// Returns:
     ///
@@ -26,18 +38,24 @@ impl Store {
         Store::default()
     }
 
-    /// The function `add_rule_set` adds a rule set to a collection.
+    /// The function `add_rule_set` adds a rule set after the others, or replaces the rule set with the
+    /// same name.
     ///
     /// Arguments:
     ///
     /// * `name`: A `String` representing the name of the rule set.
     /// * `rule_set`: The `rule_set` parameter is of type `RuleSet`.
-    pub fn add_rule_set(&mut self, name: String, rule_set: RuleSet) {
-        self.sets.insert(name, Arc::new(Mutex::new(rule_set)));
+    pub fn add_rule_set(&mut self, name: String, rule_set: RuleSet<T>) {
+        let rule_set = Arc::new(Mutex::new(rule_set));
+
+        match self.sets.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, set)) => *set = rule_set,
+            None => self.sets.push((name, rule_set)),
+        }
     }
 
     /// The function `get_rule_set` returns an `Option` containing a cloned reference to a `RuleSet` if
-    /// it exists in the `sets` HashMap.
+    /// it exists in the store.
     ///
     /// Arguments:
     ///
@@ -47,11 +65,14 @@ impl Store {
     ///
     /// The function `get_rule_set` returns an `Option` containing an `Arc` wrapped in a `Mutex` of type
     /// `RuleSet`.
-    pub fn get_rule_set(&self, name: &str) -> Option<Arc<Mutex<RuleSet>>> {
-        self.sets.get(name).cloned()
+    pub fn get_rule_set(&self, name: &str) -> Option<Arc<Mutex<RuleSet<T>>>> {
+        self.sets
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, set)| set.clone())
     }
 
-    /// The function `add_non_terminal` adds a non terminal to the store.
+    // The function `add_non_terminal` adds a non terminal to the store.
     // pub fn add_non_terminal(&mut self) {
     //     // add r to c1
     //     let ref_c1_to_r = self.r.clone();
@@ -99,15 +120,28 @@ impl Store {
     //         );
     // }
 
-    /// The function `get_all_rules` returns a vector containing all the rules from the store.
+    /// The function `get_start_rules` returns the rules of the start set, the first one added to the
+    /// store, from which the analysis of an input begins.
+    ///
+    /// Returns:
+    ///
+    /// The rules of the start set, or no rule if the store is empty.
+    pub fn get_start_rules(&self) -> Vec<Rule<T>> {
+        self.sets
+            .first()
+            .map_or(Vec::new(), |(_, set)| set.lock().unwrap().rules.clone())
+    }
+
+    /// The function `get_all_rules` returns a vector containing all the rules from the store, in the
+    /// order of their rule sets.
     ///
     /// Returns:
     ///
     /// The `get_all_rules` function returns a `Vec<Rule>`.
-    pub fn get_all_rules(&self) -> Vec<Rule> {
+    pub fn get_all_rules(&self) -> Vec<Rule<T>> {
         let mut rules = Vec::new();
 
-        for rule_set in self.sets.values() {
+        for (_, rule_set) in &self.sets {
             let rule_set = rule_set.lock().unwrap();
             for rule in &rule_set.rules {
                 rules.push(rule.clone());
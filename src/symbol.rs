@@ -0,0 +1,483 @@
+use std::{fmt, ops::Range};
+
+use crate::{
+    capture,
+    error::Error,
+    grammar::{Grammar, GrammarRule},
+    parser::Derivation,
+    terminal::Terminal,
+};
+
+/// The `Symbol` enum represents what a rule does with the symbol table when it matches.
+///
+/// Variants:
+///
+/// * `Declare`: The texts of the `captures` are declared in the `namespace`, written
+///   `declare node: name`. An identifier declared twice in the same scope is an error.
+/// * `Use`: The texts of the `captures` must be declared in the `namespace`, written
+///   `use node: from, to`. An identifier not declared before is an error.
+/// * `Scope`: The rule opens a new scope for the rules after it, written `scope`. The scope lasts
+///   until another rule of the same grammar set opens a new one, or a rule of a grammar set which
+///   opened a scope before it does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol {
+    Declare {
+        namespace: String,
+        captures: Vec<String>,
+    },
+    Use {
+        namespace: String,
+        captures: Vec<String>,
+    },
+    Scope,
+}
+
+impl Symbol {
+    /// The function `parse` reads a symbol entry of a rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the entry as written in the grammar, e.g. `use node: from`.
+    ///
+    /// Returns:
+    ///
+    /// The `Symbol`, or a message describing why the text is not a valid entry.
+    pub fn parse(text: &str) -> Result<Symbol, String> {
+        let text = text.trim();
+        if text == "scope" {
+            return Ok(Symbol::Scope);
+        }
+
+        let (action, rest) = text
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| "expected `declare`, `use` or `scope`".to_string())?;
+        let (namespace, captures) = rest
+            .split_once(':')
+            .ok_or_else(|| format!("expected `{} namespace: captures`", action))?;
+
+        let namespace = namespace.trim().to_string();
+        if namespace.is_empty() || !namespace.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("invalid namespace `{}`", namespace));
+        }
+
+        let captures: Vec<String> = captures
+            .split(',')
+            .map(|capture| capture.trim().to_string())
+            .collect();
+        if captures.iter().any(|capture| capture.is_empty()) {
+            return Err("expected the name of a capture".to_string());
+        }
+
+        match action {
+            "declare" => Ok(Symbol::Declare {
+                namespace,
+                captures,
+            }),
+            "use" => Ok(Symbol::Use {
+                namespace,
+                captures,
+            }),
+            _ => Err(format!("unknown action `{}`", action)),
+        }
+    }
+
+    /// The function `parse_all` reads the symbol entries of a rule and checks that they name
+    /// captures of the rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `rule`: The `rule` parameter is the rule of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The symbol entries of the rule, or the entry which is not valid with a message telling why.
+    pub fn parse_all(rule: &GrammarRule) -> Result<Vec<Symbol>, (String, String)> {
+        let names: Vec<&str> = rule
+            .terminals
+            .iter()
+            .filter_map(|terminal| capture::split(terminal).0)
+            .collect();
+
+        let mut symbols = Vec::new();
+        for text in &rule.symbols {
+            let symbol = Symbol::parse(text).map_err(|message| (text.clone(), message))?;
+
+            if let Symbol::Declare { captures, .. } | Symbol::Use { captures, .. } = &symbol {
+                if let Some(unknown) = captures.iter().find(|c| !names.contains(&c.as_str())) {
+                    return Err((text.clone(), format!("unknown capture `{}`", unknown)));
+                }
+            }
+            symbols.push(symbol);
+        }
+
+        Ok(symbols)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Symbol::Declare {
+                namespace,
+                captures,
+            } => write!(f, "declare {}: {}", namespace, captures.join(", ")),
+            Symbol::Use {
+                namespace,
+                captures,
+            } => write!(f, "use {}: {}", namespace, captures.join(", ")),
+            Symbol::Scope => write!(f, "scope"),
+        }
+    }
+}
+
+/// The `Declaration` struct represents an identifier declared in the symbol table.
+///
+/// Properties:
+///
+/// * `namespace`: The namespace of the identifier, e.g. `node`.
+/// * `name`: The text of the identifier.
+/// * `span`: The byte span of the identifier in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub namespace: String,
+    pub name: String,
+    pub span: Range<usize>,
+}
+
+/// The `Frame` struct represents a scope of the symbol table.
+///
+/// Properties:
+///
+/// * `owner`: The grammar set of the rule which opened the scope, `None` for the global scope.
+/// * `declarations`: The identifiers declared in the scope, in order.
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    owner: Option<String>,
+    declarations: Vec<Declaration>,
+}
+
+/// The `SymbolTable` struct represents the identifiers declared so far in the input, in nested
+/// scopes, the global scope being the outermost one.
+///
+/// Properties:
+///
+/// * `frames`: The scopes, from the outermost to the innermost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolTable {
+    frames: Vec<Frame>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolTable {
+    /// The function `new` creates a symbol table with an empty global scope.
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            frames: vec![Frame {
+                owner: None,
+                declarations: Vec::new(),
+            }],
+        }
+    }
+
+    /// The function `open` opens a new innermost scope. The scope previously opened by the same
+    /// grammar set, if any, is closed first with the scopes nested in it.
+    ///
+    /// Arguments:
+    ///
+    /// * `owner`: The `owner` parameter is the name of the grammar set of the rule opening the
+    ///   scope.
+    pub fn open(&mut self, owner: &str) {
+        if let Some(index) = self
+            .frames
+            .iter()
+            .position(|frame| frame.owner.as_deref() == Some(owner))
+        {
+            self.frames.truncate(index);
+        }
+
+        self.frames.push(Frame {
+            owner: Some(owner.to_string()),
+            declarations: Vec::new(),
+        });
+    }
+
+    /// The function `declare` declares an identifier in the innermost scope.
+    ///
+    /// Arguments:
+    ///
+    /// * `declaration`: The `declaration` parameter is the identifier to declare.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())`, or the previous declaration of the identifier in the same scope, if any.
+    pub fn declare(&mut self, declaration: Declaration) -> Result<(), Declaration> {
+        let frame = self
+            .frames
+            .last_mut()
+            .expect("the global scope is never closed");
+        if let Some(previous) = frame
+            .declarations
+            .iter()
+            .find(|d| d.namespace == declaration.namespace && d.name == declaration.name)
+        {
+            return Err(previous.clone());
+        }
+
+        frame.declarations.push(declaration);
+        Ok(())
+    }
+
+    /// The function `lookup` finds the declaration of an identifier, from the innermost scope to the
+    /// global one.
+    ///
+    /// Arguments:
+    ///
+    /// * `namespace`: The `namespace` parameter is the namespace of the identifier.
+    /// * `name`: The `name` parameter is the text of the identifier.
+    ///
+    /// Returns:
+    ///
+    /// The declaration of the identifier, or `None` if it is not declared.
+    pub fn lookup(&self, namespace: &str, name: &str) -> Option<&Declaration> {
+        self.frames.iter().rev().find_map(|frame| {
+            frame
+                .declarations
+                .iter()
+                .find(|d| d.namespace == namespace && d.name == name)
+        })
+    }
+
+    /// The function `names` returns the identifiers of a namespace which can be used, from the
+    /// innermost scope to the global one.
+    ///
+    /// Arguments:
+    ///
+    /// * `namespace`: The `namespace` parameter is the namespace of the identifiers.
+    ///
+    /// Returns:
+    ///
+    /// The texts of the declared identifiers, without duplicates.
+    pub fn names(&self, namespace: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for declaration in self
+            .frames
+            .iter()
+            .rev()
+            .flat_map(|frame| &frame.declarations)
+        {
+            if declaration.namespace == namespace && !names.contains(&declaration.name.as_str()) {
+                names.push(declaration.name.as_str());
+            }
+        }
+
+        names
+    }
+}
+
+/// The function `check` fills a symbol table with the rules which matched an input, in order, and
+/// reports the identifiers used before they are declared and the ones declared twice.
+///
+/// The entries of a rule are applied in the order they are written, and a scope opened by a rule
+/// starts after it: the rule declares its own identifiers in the enclosing scope.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the grammar the input was parsed with.
+/// * `derivation`: The `derivation` parameter is the list of rules which matched the input (see
+///   `Parser::derivation`).
+///
+/// Returns:
+///
+/// The `Undeclared` and `Redeclared` errors of the input, in order, empty if its identifiers are
+/// consistent.
+pub fn check<T: Terminal>(grammar: &Grammar, derivation: &[Derivation<T>]) -> Vec<Error> {
+    let mut table = SymbolTable::new();
+    let mut errors = Vec::new();
+
+    for matched in derivation {
+        let Some(rule) = grammar
+            .sets
+            .iter()
+            .find(|set| set.name == matched.name)
+            .and_then(|set| set.rules.get(matched.index))
+        else {
+            continue;
+        };
+        // the grammar was checked when it was compiled
+        let symbols = Symbol::parse_all(rule).unwrap_or_default();
+
+        // the identifier of a capture, the end of the input having none
+        let identifier = |name: &str| {
+            let position = rule
+                .terminals
+                .iter()
+                .position(|terminal| capture::split(terminal).0 == Some(name))?;
+            matched.lexemes.get(position)?.as_ref()
+        };
+
+        for symbol in &symbols {
+            match symbol {
+                Symbol::Declare {
+                    namespace,
+                    captures,
+                } => {
+                    for lexeme in captures.iter().filter_map(|name| identifier(name)) {
+                        let declaration = Declaration {
+                            namespace: namespace.clone(),
+                            name: lexeme.text.clone(),
+                            span: lexeme.span.clone(),
+                        };
+                        if let Err(previous) = table.declare(declaration) {
+                            errors.push(Error::Redeclared {
+                                span: lexeme.span.clone(),
+                                namespace: namespace.clone(),
+                                name: lexeme.text.clone(),
+                                previous: previous.span,
+                            });
+                        }
+                    }
+                }
+                Symbol::Use {
+                    namespace,
+                    captures,
+                } => {
+                    for lexeme in captures.iter().filter_map(|name| identifier(name)) {
+                        if table.lookup(namespace, &lexeme.text).is_none() {
+                            errors.push(Error::Undeclared {
+                                span: lexeme.span.clone(),
+                                namespace: namespace.clone(),
+                                name: lexeme.text.clone(),
+                            });
+                        }
+                    }
+                }
+                Symbol::Scope => {}
+            }
+        }
+
+        if symbols.contains(&Symbol::Scope) {
+            table.open(&matched.name);
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report;
+
+    const GRAMMAR: &str = "S -> N\nN -> identifier[node] name:identifier N { declare node: name } \
+                           | C\nC -> contact from:identifier to:identifier R { use node: from, \
+                           to; scope } | None\nR -> rate label:identifier R { declare rate: \
+                           label } | C";
+
+    fn errors(input: &str) -> Vec<Error> {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+        report::validate(&grammar, input).unwrap().errors
+    }
+
+    fn declaration(name: &str, span: Range<usize>) -> Declaration {
+        Declaration {
+            namespace: "node".to_string(),
+            name: name.to_string(),
+            span,
+        }
+    }
+
+    #[test]
+    fn parses_symbol_entries() {
+        assert_eq!(
+            Symbol::parse("use node: from, to"),
+            Ok(Symbol::Use {
+                namespace: "node".to_string(),
+                captures: vec!["from".to_string(), "to".to_string()],
+            })
+        );
+        assert_eq!(Symbol::parse(" scope "), Ok(Symbol::Scope));
+        assert_eq!(
+            Symbol::parse("declare node: name").unwrap().to_string(),
+            "declare node: name"
+        );
+        assert!(Symbol::parse("define node: name").is_err());
+        assert!(Symbol::parse("use node").is_err());
+        assert!(Symbol::parse("use no de: name").is_err());
+        assert!(Symbol::parse("use node: a,").is_err());
+    }
+
+    #[test]
+    fn looks_up_declarations_through_scopes() {
+        let mut table = SymbolTable::new();
+        table.declare(declaration("A", 0..1)).unwrap();
+        table.open("C");
+        table.declare(declaration("B", 2..3)).unwrap();
+        // an identifier of an enclosing scope may be declared again
+        table.declare(declaration("A", 4..5)).unwrap();
+        assert_eq!(
+            table.declare(declaration("B", 6..7)),
+            Err(declaration("B", 2..3))
+        );
+        assert_eq!(table.lookup("node", "A"), Some(&declaration("A", 4..5)));
+        assert_eq!(table.names("node"), vec!["B", "A"]);
+
+        // the set closes its previous scope when it opens a new one
+        table.open("C");
+        assert_eq!(table.lookup("node", "B"), None);
+        assert_eq!(table.lookup("node", "A"), Some(&declaration("A", 0..1)));
+        assert_eq!(table.lookup("rate", "A"), None);
+    }
+
+    #[test]
+    fn accepts_declared_identifiers() {
+        assert!(
+            errors("node A\nnode B\ncontact A B\nrate low\ncontact B A\nrate low\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn reports_an_undeclared_identifier() {
+        assert!(
+            matches!(&errors("node A\ncontact A E\n")[..], [Error::Undeclared { span, namespace, name }]
+                if *span == (17..18) && namespace == "node" && name == "E")
+        );
+    }
+
+    #[test]
+    fn reports_a_redeclared_identifier() {
+        let errors = errors("node A\nnode A\ncontact A A\nrate low\nrate low\n");
+
+        assert!(
+            matches!(&errors[..], [
+                Error::Redeclared { span, previous, name, .. },
+                Error::Redeclared { span: second, previous: first, .. },
+            ] if *span == (12..13) && *previous == (5..6) && name == "A"
+                && *second == (40..43) && *first == (31..34)),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn reports_the_same_errors_in_the_same_order_on_each_run() {
+        // each run compiles the grammar again, as each run of the command would
+        let input = "node A\nnode A\ncontact A B\nrate low\nrate low\ncontact C A\n";
+        let describe = |errors: Vec<Error>| -> Vec<(String, Option<Range<usize>>)> {
+            errors
+                .iter()
+                .map(|error| (error.to_string(), error.span()))
+                .collect()
+        };
+
+        let first = describe(errors(input));
+        assert_eq!(first.len(), 4, "{:?}", first);
+        for _ in 0..20 {
+            assert_eq!(describe(errors(input)), first);
+        }
+    }
+}
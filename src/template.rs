@@ -0,0 +1,332 @@
+use std::fmt;
+
+use crate::{
+    attribute::{self, Attribute},
+    capture,
+    error::Error,
+    expression::Value,
+    grammar::{Grammar, GrammarRule},
+    parser::Derivation,
+    terminal::Terminal,
+};
+
+/// The `Part` enum represents a part of a template.
+///
+/// Variants:
+///
+/// * `Text`: A text copied as is.
+/// * `Placeholder`: The name of a capture or of an attribute of the rule, written `${name}`,
+///   replaced by its text or its value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part {
+    Text(String),
+    Placeholder(String),
+}
+
+/// The `Template` struct represents the output of a rule, written once the input is accepted to
+/// translate it into another format, e.g. `INSERT INTO contacts VALUES ('${from}', '${to}');`.
+///
+/// Properties:
+///
+/// * `parts`: The texts and the placeholders of the template, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub parts: Vec<Part>,
+}
+
+impl Template {
+    /// The function `parse` reads a template, where `${name}` is a placeholder and `$$` is a `$`.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the template as written in the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The `Template`, or a message describing why the text is not a valid template.
+    pub fn parse(text: &str) -> Result<Template, String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut rest = text;
+
+        while let Some(index) = rest.find('$') {
+            current.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+
+            if let Some(after) = rest.strip_prefix('$') {
+                current.push('$');
+                rest = after;
+                continue;
+            }
+
+            let Some(after) = rest.strip_prefix('{') else {
+                return Err("expected `{` or `$` after `$`".to_string());
+            };
+            let (name, after) = after
+                .split_once('}')
+                .ok_or_else(|| "expected `}` after the placeholder".to_string())?;
+
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(format!("invalid placeholder `{}`", name));
+            }
+
+            if !current.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut current)));
+            }
+            parts.push(Part::Placeholder(name.to_string()));
+            rest = after;
+        }
+
+        current.push_str(rest);
+        if !current.is_empty() {
+            parts.push(Part::Text(current));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// The function `compile` reads the template of a rule and checks that its placeholders are
+    /// captures or attributes of the rule.
+    ///
+    /// Arguments:
+    ///
+    /// * `rule`: The `rule` parameter is the rule of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The template of the rule, if any, or a message describing why it is not valid.
+    pub fn compile(rule: &GrammarRule) -> Result<Option<Template>, String> {
+        let Some(text) = &rule.template else {
+            return Ok(None);
+        };
+        let template = Template::parse(text)?;
+
+        // the attributes were checked before
+        let attributes = Attribute::parse_all(rule).unwrap_or_default();
+        let known = |name: &str| {
+            rule.terminals
+                .iter()
+                .any(|terminal| capture::split(terminal).0 == Some(name))
+                || attributes.iter().any(|attribute| attribute.name == name)
+        };
+
+        for part in &template.parts {
+            if let Part::Placeholder(name) = part {
+                if !known(name) {
+                    return Err(format!("unknown name `{}`", name));
+                }
+            }
+        }
+
+        Ok(Some(template))
+    }
+
+    /// The function `render` writes the template with the value of each placeholder.
+    ///
+    /// Arguments:
+    ///
+    /// * `value`: The `value` parameter returns the text of a placeholder from its name.
+    ///
+    /// Returns:
+    ///
+    /// The output of the rule.
+    pub fn render(&self, mut value: impl FnMut(&str) -> String) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Placeholder(name) => value(name),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => write!(f, "{}", text.replace('$', "$$"))?,
+                Part::Placeholder(name) => write!(f, "${{{}}}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The function `quote` writes a template between double quotes as in the text notation, escaping
+/// the quotes, the backslashes and the line breaks.
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The function `unquote` reads a template written between double quotes, see `quote`.
+///
+/// Arguments:
+///
+/// * `text`: The `text` parameter is the quoted template.
+///
+/// Returns:
+///
+/// The template, or a message describing why the text is not a quoted string.
+pub fn unquote(text: &str) -> Result<String, String> {
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .ok_or_else(|| "expected a template between double quotes".to_string())?;
+
+    let mut unquoted = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unquoted.push('\n'),
+                Some('t') => unquoted.push('\t'),
+                Some(c @ ('"' | '\\')) => unquoted.push(c),
+                _ => return Err("invalid escape in the template".to_string()),
+            },
+            '"' => return Err("unescaped `\"` in the template".to_string()),
+            c => unquoted.push(c),
+        }
+    }
+
+    Ok(unquoted)
+}
+
+/// The function `translate` writes the output of the rules which matched an input, in order.
+///
+/// Arguments:
+///
+/// * `grammar`: The `grammar` parameter is the grammar the input was parsed with.
+/// * `derivation`: The `derivation` parameter is the list of rules which matched the input (see
+///   `Parser::derivation`).
+///
+/// Returns:
+///
+/// The output of each matched rule with a template, or an `Attribute` error if an attribute read
+/// by a template cannot be computed.
+pub fn translate<T: Terminal>(
+    grammar: &Grammar,
+    derivation: &[Derivation<T>],
+) -> Result<Vec<String>, Error> {
+    // the attributes are listed for the matched rules which have some, in order
+    let mut attributes = attribute::evaluate(grammar, derivation)?.into_iter();
+    let mut output = Vec::new();
+
+    for matched in derivation {
+        let rule = grammar
+            .sets
+            .iter()
+            .find(|set| set.name == matched.name)
+            .and_then(|set| set.rules.get(matched.index))
+            .expect("the derivation comes from the grammar");
+        let values = match rule.attributes.is_empty() {
+            true => Vec::new(),
+            false => attributes.next().map_or(Vec::new(), |a| a.values),
+        };
+
+        // the grammar was checked when it was compiled
+        let Ok(Some(template)) = Template::compile(rule) else {
+            continue;
+        };
+
+        output.push(template.render(|name| {
+            if let Some((_, value)) = values.iter().find(|(other, _)| other == name) {
+                return match value {
+                    Value::Text(text) => text.clone(),
+                    value => value.to_string(),
+                };
+            }
+
+            rule.terminals
+                .iter()
+                .position(|terminal| capture::split(terminal).0 == Some(name))
+                .and_then(|position| matched.lexemes.get(position)?.as_ref())
+                .map_or(String::new(), |lexeme| lexeme.text.clone())
+        }));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR: &str = "S -> C { emit \"[\" }\nC -> contact from:identifier to:identifier \
+                           start:number end:number C { duration = end - start; emit \
+                           \"${from} -> ${to}: ${duration}$$\" } | None { emit \"]\" }";
+
+    #[test]
+    fn parses_placeholders_and_dollars() {
+        let template = Template::parse("a ${from}$$ ${ to }").unwrap();
+
+        assert_eq!(
+            template.parts,
+            vec![
+                Part::Text("a ".to_string()),
+                Part::Placeholder("from".to_string()),
+                Part::Text("$ ".to_string()),
+                Part::Placeholder("to".to_string()),
+            ]
+        );
+        assert_eq!(template.to_string(), "a ${from}$$ ${to}");
+        assert!(Template::parse("$from").is_err());
+        assert!(Template::parse("${from").is_err());
+        assert!(Template::parse("${a b}").is_err());
+    }
+
+    #[test]
+    fn renders_the_value_of_each_placeholder() {
+        let template = Template::parse("${from} -> ${to}").unwrap();
+
+        assert_eq!(template.render(|name| name.to_uppercase()), "FROM -> TO");
+    }
+
+    #[test]
+    fn quotes_and_unquotes_templates() {
+        let text = "say \"hi\"\\\n";
+
+        assert_eq!(quote(text), "\"say \\\"hi\\\"\\\\\\n\"");
+        assert_eq!(unquote(&quote(text)), Ok(text.to_string()));
+        assert!(unquote("no quotes").is_err());
+        assert!(unquote("\"a\"b\"").is_err());
+        assert!(unquote("\"\\x\"").is_err());
+    }
+
+    #[test]
+    fn translates_an_accepted_input() {
+        let grammar = Grammar::from_text(GRAMMAR).unwrap().compile().unwrap();
+
+        assert_eq!(
+            grammar
+                .translate("contact A B 10 25\ncontact B C 30 40\n")
+                .unwrap(),
+            Some(vec![
+                "[".to_string(),
+                "A -> B: 15$".to_string(),
+                "B -> C: 10$".to_string(),
+                "]".to_string(),
+            ])
+        );
+        assert_eq!(grammar.translate("contact A").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        let grammar = Grammar::from_text("S -> rate n:number { emit \"${m}\" }").unwrap();
+
+        assert!(grammar.compile().is_err());
+    }
+}
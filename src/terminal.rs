@@ -0,0 +1,233 @@
+use std::fmt;
+
+use logos::{Lexer, Logos};
+
+use crate::lexer::Lexeme;
+
+/// The `Terminal` trait is implemented by the tokens the `Parser` can analyze. The crate's own
+/// `Token` implements it, and so can any other type, such as another logos enum.
+pub trait Terminal: Clone + PartialEq + fmt::Debug {
+    /// The function `from_name` returns the token named by a terminal of a grammar.
+    ///
+    /// Arguments:
+    ///
+    /// * `name`: The `name` parameter is the name of the terminal in the grammar.
+    ///
+    /// Returns:
+    ///
+    /// The token, or `None` if the name is not the one of a token.
+    fn from_name(name: &str) -> Option<Self>;
+
+    /// The function `name` returns the name of the token displayed in traces and errors.
+    fn name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// The function `is_end` returns true if the token is the terminal matching the end of the input.
+    fn is_end(&self) -> bool {
+        false
+    }
+
+    /// The function `value` returns the value of a token read from the given text, e.g. the integer
+    /// of a number.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is the text matched by the token.
+    ///
+    /// Returns:
+    ///
+    /// The value, or `None` if the token has no value.
+    fn value(&self, _text: &str) -> Option<u64> {
+        None
+    }
+}
+
+/// The `Tokens` struct adapts a logos `Lexer` into the tokens of a `Parser`: each token is read as a
+/// `Lexeme` with its text and location, the token being `None` for an invalid one.
+///
+/// Properties:
+///
+/// * `lexer`: The lexer producing the tokens.
+/// * `line`: The line of the end of the last token read.
+/// * `column`: The column of the start of the last token read, counted in characters.
+/// * `offset`: The byte offset of the start of the last token read, the location being updated
+///   from one token to the next instead of counting from the start of the input.
+pub struct Tokens<'a, L: Logos<'a>> {
+    lexer: Lexer<'a, L>,
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl<'a, L: Logos<'a>> Tokens<'a, L> {
+    /// The function `new` creates a new instance of the `Tokens` struct over the given lexer.
+    ///
+    /// Arguments:
+    ///
+    /// * `lexer`: The `lexer` parameter is the lexer producing the tokens.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Tokens` struct is being returned.
+    pub fn new(lexer: Lexer<'a, L>) -> Tokens<'a, L> {
+        Tokens {
+            lexer,
+            line: 1,
+            column: 1,
+            offset: 0,
+        }
+    }
+}
+
+impl<'a, L> Clone for Tokens<'a, L>
+where
+    L: Logos<'a> + Clone,
+    L::Extras: Clone,
+{
+    fn clone(&self) -> Self {
+        Tokens {
+            lexer: self.lexer.clone(),
+            line: self.line,
+            column: self.column,
+            offset: self.offset,
+        }
+    }
+}
+
+impl<'a, L: Logos<'a, Source = str> + Terminal> Iterator for Tokens<'a, L> {
+    type Item = Lexeme<L>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lexer.next()?;
+        let span = self.lexer.span();
+
+        // count the lines and the characters between the previous token and this one
+        let between = &self.lexer.source()[self.offset..span.start];
+        match between.rfind('\n') {
+            Some(index) => {
+                self.line += between.matches('\n').count();
+                self.column = between[index + 1..].chars().count() + 1;
+            }
+            None => self.column += between.chars().count(),
+        }
+        self.offset = span.start;
+
+        Some(Lexeme::new(
+            token.ok(),
+            self.lexer.slice().to_string(),
+            span.clone(),
+            self.line,
+            self.column,
+        ))
+    }
+}
+
+/// The function `indexed` adapts any sequence of tokens into the tokens of a `Parser`, the span of
+/// each token being its index in the sequence and its text being its name.
+///
+/// Arguments:
+///
+/// * `tokens`: The `tokens` parameter is the sequence of tokens.
+///
+/// Returns:
+///
+/// an iterator over the lexemes of the tokens.
+pub fn indexed<T, I>(tokens: I) -> impl Iterator<Item = Lexeme<T>> + Clone
+where
+    T: Terminal,
+    I: IntoIterator<Item = T>,
+    I::IntoIter: Clone,
+{
+    tokens.into_iter().enumerate().map(|(index, token)| {
+        let text = token.name();
+        Lexeme::new(Some(token), text, index..index + 1, 1, index + 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grammar::Grammar, parser::Parser, store::Store};
+
+    /// The `Door` enum is a terminal of another lexer than the crate's `Token`.
+    #[derive(Logos, Debug, Clone, PartialEq)]
+    #[logos(skip r"[ \n]+")]
+    enum Door {
+        #[token("open")]
+        Open,
+
+        #[token("close")]
+        Close,
+
+        #[regex("[0-9]+")]
+        Count,
+    }
+
+    impl Terminal for Door {
+        fn from_name(name: &str) -> Option<Self> {
+            match name {
+                "Open" => Some(Door::Open),
+                "Close" => Some(Door::Close),
+                "Count" => Some(Door::Count),
+                _ => None,
+            }
+        }
+
+        fn value(&self, text: &str) -> Option<u64> {
+            match self {
+                Door::Count => text.parse().ok(),
+                _ => None,
+            }
+        }
+    }
+
+    fn store() -> Store<Door> {
+        Grammar::from_text("S -> open count S | close")
+            .unwrap()
+            .to_store()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_another_terminal_type() {
+        let input = "open 2\nopen 10\nclose";
+        let mut parser = Parser::from_tokens(store(), Tokens::new(Door::lexer(input)));
+
+        assert!(parser.parse());
+        let lexemes: Vec<&Lexeme<Door>> = parser
+            .derivation()
+            .iter()
+            .flat_map(|derivation| derivation.lexemes.iter().flatten())
+            .collect();
+        assert_eq!(lexemes.len(), 5);
+        assert_eq!(lexemes[3].text, "10");
+        assert_eq!(lexemes[3].value, Some(10));
+        assert_eq!((lexemes[3].line, lexemes[3].column), (2, 6));
+        assert_eq!(lexemes[4].span, 15..20);
+
+        let mut parser = Parser::from_tokens(store(), Tokens::new(Door::lexer("open close")));
+        assert!(!parser.parse());
+    }
+
+    #[test]
+    fn rejects_an_unknown_terminal_name() {
+        let grammar = Grammar::from_text("S -> open contact").unwrap();
+
+        assert!(grammar.to_store::<Door>().is_err());
+    }
+
+    #[test]
+    fn parses_an_indexed_sequence() {
+        let tokens = indexed(vec![Door::Open, Door::Count, Door::Close]);
+        let lexemes: Vec<Lexeme<Door>> = tokens.clone().collect();
+
+        assert_eq!(lexemes[1].text, "Count");
+        assert_eq!(lexemes[1].span, 1..2);
+        assert_eq!((lexemes[1].line, lexemes[1].column), (1, 2));
+        assert!(Parser::from_tokens(store(), tokens).parse());
+
+        let tokens = indexed(vec![Door::Open, Door::Close]);
+        assert!(!Parser::from_tokens(store(), tokens).parse());
+    }
+}
@@ -0,0 +1,729 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use logos::Logos;
+
+use crate::{
+    error::{Error, GrammarErrorKind},
+    expression::Value,
+    parser::Token,
+    predicate::Predicate,
+    rules::RuleStep,
+    store::Store,
+    terminal::Terminal,
+};
+
+/// The `Prefix` struct describes how a partial input relates to the sentences of a grammar.
+///
+/// Properties:
+///
+/// * `viable`: True if the input is a prefix of at least one sentence accepted by the grammar.
+/// * `accepted`: True if the input is itself accepted by the grammar.
+/// * `expected`: The tokens which may come next, empty if the input is not viable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prefix {
+    pub viable: bool,
+    pub accepted: bool,
+    pub expected: Vec<Token>,
+}
+
+/// The function `analyze_prefix` checks whether the given partial input can still be extended into a
+/// sentence accepted by the grammar, and lists the tokens which may come next.
+///
+/// Arguments:
+///
+/// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+/// * `input`: The `input` parameter is the partial input. Its last token is considered complete.
+///
+/// Returns:
+///
+/// a `Prefix` describing the partial input.
+pub fn analyze_prefix(store: &Store, input: &str) -> Prefix {
+    let mut validator = Validator::new(store);
+    let viable = validator
+        .feed(input)
+        .and_then(|_| validator.flush())
+        .is_ok();
+
+    Prefix {
+        viable,
+        accepted: viable && validator.is_accepting(),
+        expected: match viable {
+            true => validator.expected(),
+            false => Vec::new(),
+        },
+    }
+}
+
+/// The `Position` struct represents a position inside the steps of a rule, i.e. the steps that
+/// have already been matched and the ones that are still expected.
+///
+/// Properties:
+///
+/// * `steps`: The steps of the rule, shared with the `Store` the position comes from.
+/// * `index`: The index of the next step to match. When it is equal to the number of steps, the
+///   rule has been fully matched.
+/// * `values`: The value of each token matched by the rule so far, read by its predicates (see
+///   `Value::of`), or `None` if the texts of the tokens are unknown and the predicates are not
+///   checked.
+#[derive(Clone)]
+pub(crate) struct Position<T = Token> {
+    pub(crate) steps: Arc<Mutex<Vec<RuleStep<T>>>>,
+    pub(crate) index: usize,
+    pub(crate) values: Option<Vec<Result<Value, String>>>,
+}
+
+impl<T> Position<T> {
+    /// The function `same` returns true if both positions point to the same step of the same rule.
+    fn same(&self, other: &Position<T>) -> bool {
+        Arc::ptr_eq(&self.steps, &other.steps) && self.index == other.index
+    }
+}
+
+/// The `Validator` struct is an incremental (push) version of the `Parser`: the input is given in
+/// chunks and the state of the analysis is kept between them.
+///
+/// Instead of backtracking over a lexer, the validator keeps every position of the grammar that is
+/// compatible with the tokens read so far. The input is rejected as soon as no position is left,
+/// meaning that the prefix can no longer be extended to an accepted sentence.
+///
+/// Properties:
+///
+/// * `positions`: The positions of the grammar compatible with the tokens read so far.
+/// * `pending`: The end of the last chunk which may still be the beginning of a longer token.
+/// * `offset`: The byte offset of `pending` in the whole input.
+/// * `end`: The byte offset of the end of the last token, where a missing token is reported.
+/// * `error`: The error which rejected the input, if any. It is never set when recovering from
+///   errors.
+/// * `errors`: All the errors found so far.
+/// * `start`: The positions the analysis started from, used to resynchronize after an error.
+/// * `sync`: The tokens on which the analysis resynchronizes after an error, or `None` if the
+///   analysis stops at the first error.
+/// * `recovering`: True while the tokens are skipped until a synchronization token.
+pub struct Validator {
+    positions: Vec<Position>,
+    pending: String,
+    offset: usize,
+    end: usize,
+    error: Option<Error>,
+    errors: Vec<Error>,
+    start: Vec<Position>,
+    sync: Option<Vec<Token>>,
+    recovering: bool,
+}
+
+impl Validator {
+    /// The function `new` creates a new instance of the `Validator` struct which, like
+    /// `Parser::parse`, accepts the input if a rule of the start set of the store matches it.
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Validator` struct is being returned.
+    pub fn new(store: &Store) -> Validator {
+        let positions = store
+            .get_start_rules()
+            .into_iter()
+            .map(|rule| Position {
+                steps: rule.steps,
+                index: 0,
+                values: Some(Vec::new()),
+            })
+            .collect();
+
+        Self::from_positions(positions)
+    }
+
+    /// The function `from_rule_set` creates a new instance of the `Validator` struct which accepts
+    /// the input only if it matches one of the rules of the given rule set.
+    ///
+    /// Arguments:
+    ///
+    /// * `store`: The `store` parameter is the `Store` containing the rules of the grammar.
+    /// * `name`: The `name` parameter is the name of the rule set the input must match.
+    ///
+    /// Returns:
+    ///
+    /// A new instance of the `Validator` struct, or an error if the store has no rule set with the
+    /// given name.
+    pub fn from_rule_set(store: &Store, name: &str) -> Result<Validator, Error> {
+        let rule_set = store.get_rule_set(name).ok_or_else(|| Error::Grammar {
+            set: None,
+            rule: None,
+            kind: GrammarErrorKind::UnknownSet(name.to_string()),
+        })?;
+        let positions = rule_set
+            .lock()
+            .unwrap()
+            .rules
+            .iter()
+            .map(|rule| Position {
+                steps: rule.steps.clone(),
+                index: 0,
+                values: Some(Vec::new()),
+            })
+            .collect();
+
+        Ok(Self::from_positions(positions))
+    }
+
+    /// The function `from_positions` creates a new instance of the `Validator` struct starting from
+    /// the given positions.
+    fn from_positions(positions: Vec<Position>) -> Validator {
+        let positions = Self::closure(positions);
+
+        Validator {
+            positions: positions.clone(),
+            pending: String::new(),
+            offset: 0,
+            end: 0,
+            error: None,
+            errors: Vec::new(),
+            start: positions,
+            sync: None,
+            recovering: false,
+        }
+    }
+
+    /// The function `with_recovery` makes the validator report every error of the input instead of
+    /// stopping at the first one.
+    ///
+    /// After an error, the tokens are skipped until one of the synchronization tokens from which the
+    /// analysis can start again (panic-mode recovery).
+    ///
+    /// Arguments:
+    ///
+    /// * `sync`: The `sync` parameter is the list of synchronization tokens, usually the ones
+    ///   declared by the grammar. If it is empty, every token which can start a rule is used.
+    ///
+    /// Returns:
+    ///
+    /// The `Validator` with error recovery enabled.
+    pub fn with_recovery(mut self, sync: Vec<Token>) -> Validator {
+        let sync = match sync.is_empty() {
+            true => Self::expected_from(&self.start),
+            false => sync,
+        };
+
+        self.sync = Some(sync);
+        self
+    }
+
+    /// The function `feed` analyzes the next chunk of the input.
+    ///
+    /// A token may be split between two chunks, so the text after the last whitespace of the chunk
+    /// is only analyzed with the next chunk or when calling `finish`.
+    ///
+    /// Arguments:
+    ///
+    /// * `chunk`: The `chunk` parameter is the next part of the input.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the input read so far is still a prefix of an accepted sentence, or the error
+    /// which rejected the input otherwise. Once an error is returned, every following call returns
+    /// it again.
+    pub fn feed(&mut self, chunk: &str) -> Result<(), Error> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        self.pending.push_str(chunk);
+
+        // only analyze the text up to the last whitespace, tokens never contain whitespace
+        let split = match self.pending.rfind([' ', '\t', '\n', '\x0C']) {
+            Some(index) => index + 1,
+            None => return Ok(()),
+        };
+
+        let text: String = self.pending.drain(..split).collect();
+        let result = self.consume(&text);
+        self.offset += split;
+
+        result
+    }
+
+    /// The function `finish` analyzes the rest of the input and checks that the whole input is
+    /// accepted by the grammar.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the input is accepted, or the first error which rejected the input otherwise.
+    pub fn finish(self) -> Result<(), Error> {
+        match self.finish_all().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// The function `finish_all` analyzes the rest of the input and returns all the errors found in
+    /// the input.
+    ///
+    /// Returns:
+    ///
+    /// The errors found in the input, in order, empty if the input is accepted. Unless recovering
+    /// from errors, there is at most one error.
+    pub fn finish_all(mut self) -> Vec<Error> {
+        let _ = self.flush();
+
+        // the input ended in the middle of a sentence, or a predicate of the rule ended by the end
+        // of the input does not hold, the skipped part was already reported
+        if self.error.is_none() && !self.recovering && !self.is_accepting() {
+            let error = match Self::failed(&self.positions, &Token::End, "")
+                .into_iter()
+                .next()
+            {
+                Some(predicate) => Error::Predicate {
+                    span: self.end..self.end,
+                    set: predicate.set,
+                    rule: predicate.rule,
+                    name: predicate.name,
+                },
+                None => Error::Syntax {
+                    span: self.end..self.end,
+                    found: None,
+                    expected: self.expected(),
+                },
+            };
+            self.errors.push(error);
+        }
+
+        self.errors
+    }
+
+    /// The function `flush` analyzes the text kept from the previous chunks, considering that its
+    /// last token is complete.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the input read so far is still a prefix of an accepted sentence, or the error
+    /// which rejected the input otherwise.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        let text = std::mem::take(&mut self.pending);
+        let result = self.consume(&text);
+        self.offset += text.len();
+
+        result
+    }
+
+    /// The function `expected` returns the tokens which may come after the input read so far.
+    ///
+    /// Returns:
+    ///
+    /// The tokens expected by the grammar, without duplicates, in the order of the grammar.
+    pub fn expected(&self) -> Vec<Token> {
+        Self::expected_from(&self.positions)
+    }
+
+    /// The function `expected_from` returns the tokens expected by the given positions.
+    pub(crate) fn expected_from<T: Terminal>(positions: &[Position<T>]) -> Vec<T> {
+        let mut expected: Vec<T> = Vec::new();
+
+        for position in positions {
+            let steps = position.steps.lock().unwrap();
+            if let Some(token) = steps
+                .get(position.index)
+                .and_then(|step| step.token.clone())
+            {
+                if !expected.contains(&token) {
+                    expected.push(token);
+                }
+            }
+        }
+
+        expected
+    }
+
+    /// The function `is_accepting` returns true if the input read so far is accepted by the grammar,
+    /// the terminal of the end of the input matching the end of the input.
+    pub fn is_accepting(&self) -> bool {
+        let ended = Self::advance(&self.positions, &Token::End, Some(""));
+
+        self.positions
+            .iter()
+            .chain(ended.iter())
+            .any(|position| position.index == position.steps.lock().unwrap().len())
+    }
+
+    /// The function `consume` lexes the given text and advances the positions with each token.
+    ///
+    /// Arguments:
+    ///
+    /// * `text`: The `text` parameter is a part of the input which does not end inside a token.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if all the tokens were consumed, or the first error found in the text otherwise.
+    fn consume(&mut self, text: &str) -> Result<(), Error> {
+        let mut first = None;
+
+        for (token, span) in Token::lexer(text).spanned() {
+            let lexeme = &text[span.clone()];
+            let span = span.start + self.offset..span.end + self.offset;
+
+            let token = match token {
+                Ok(token) => token,
+                Err(_) => {
+                    self.reject(Error::Lex { span }, &mut first)?;
+                    continue;
+                }
+            };
+
+            if !self.recovering {
+                let positions = Self::advance(&self.positions, &token, Some(lexeme));
+                if !positions.is_empty() {
+                    self.positions = positions;
+                    self.end = span.end;
+                    continue;
+                }
+
+                // the token completes a rule whose predicate does not hold, or the kind of the
+                // token is expected and only its text is not
+                let failed = Self::failed(&self.positions, &token, lexeme);
+                let constrained = Self::constrained(&self.positions, &token);
+                let error = match (failed.into_iter().next(), constrained.is_empty()) {
+                    (Some(predicate), _) => Error::Predicate {
+                        span: span.clone(),
+                        set: predicate.set,
+                        rule: predicate.rule,
+                        name: predicate.name,
+                    },
+                    (None, true) => Error::Syntax {
+                        span: span.clone(),
+                        found: Some(token.clone()),
+                        expected: self.expected(),
+                    },
+                    (None, false) => Error::Constraint {
+                        span: span.clone(),
+                        found: token.clone(),
+                        text: lexeme.to_string(),
+                        expected: constrained,
+                    },
+                };
+                self.reject(error, &mut first)?;
+            }
+
+            // skip the tokens until the analysis can start again from a synchronization token
+            if self.sync.as_ref().is_some_and(|sync| sync.contains(&token)) {
+                let positions = Self::advance(&self.start, &token, Some(lexeme));
+                if !positions.is_empty() {
+                    self.positions = positions;
+                    self.end = span.end;
+                    self.recovering = false;
+                }
+            }
+        }
+
+        match first {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// The function `reject` records an error of the input.
+    ///
+    /// Arguments:
+    ///
+    /// * `error`: The error found in the input.
+    /// * `first`: The first error found in the text being consumed, set if it is still empty.
+    ///
+    /// Returns:
+    ///
+    /// `Ok(())` if the analysis recovers from the error, or the error otherwise.
+    fn reject(&mut self, error: Error, first: &mut Option<Error>) -> Result<(), Error> {
+        self.errors.push(error.clone());
+
+        if self.sync.is_none() {
+            self.error = Some(error.clone());
+            return Err(error);
+        }
+
+        self.recovering = true;
+        first.get_or_insert(error);
+        Ok(())
+    }
+
+    /// The function `advance` returns the positions reached by matching the given token from the
+    /// given positions.
+    ///
+    /// Arguments:
+    ///
+    /// * `positions`: The positions waiting for a token.
+    /// * `token`: The token to match.
+    /// * `text`: The text of the token checked against the constraints of the steps and read by the
+    ///   predicates of the rules, or `None` to only match the kind of the token.
+    ///
+    /// Returns:
+    ///
+    /// The positions following the matched token, empty if none of the positions accepted it.
+    pub(crate) fn advance<T: Terminal>(
+        positions: &[Position<T>],
+        token: &T,
+        text: Option<&str>,
+    ) -> Vec<Position<T>> {
+        Self::expand(Self::matched(positions, token, text), &mut Vec::new())
+    }
+
+    /// The function `matched` returns the positions following the given token, before their
+    /// closure.
+    fn matched<T: Terminal>(
+        positions: &[Position<T>],
+        token: &T,
+        text: Option<&str>,
+    ) -> Vec<Position<T>> {
+        let mut next = Vec::new();
+
+        for position in positions {
+            let steps = position.steps.lock().unwrap();
+            let matches = match steps.get(position.index) {
+                Some(step) => step.matches(token, text),
+                None => false,
+            };
+            drop(steps);
+
+            if matches {
+                // the predicates of the rule read the values of its tokens
+                let values = match (&position.values, text) {
+                    (Some(values), Some(text)) => {
+                        let mut values = values.clone();
+                        values.push(Value::of(text, token.value(text)));
+                        Some(values)
+                    }
+                    _ => None,
+                };
+
+                next.push(Position {
+                    steps: position.steps.clone(),
+                    index: position.index + 1,
+                    values,
+                });
+            }
+        }
+
+        next
+    }
+
+    /// The function `failed` returns the predicates which do not hold once the given token is
+    /// matched, in the rules it completes.
+    fn failed<T: Terminal>(positions: &[Position<T>], token: &T, text: &str) -> Vec<Predicate> {
+        let mut failed = Vec::new();
+        Self::expand(Self::matched(positions, token, Some(text)), &mut failed);
+        failed
+    }
+
+    /// The function `constrained` returns the constrained terminals of the given positions which
+    /// expect the kind of the given token, e.g. `Number[..100]` for a number.
+    fn constrained<T: Terminal>(positions: &[Position<T>], token: &T) -> Vec<String> {
+        let mut expected: Vec<String> = Vec::new();
+
+        for position in positions {
+            let steps = position.steps.lock().unwrap();
+            if let Some(terminal) = steps
+                .get(position.index)
+                .filter(|step| step.constraint.is_some() && step.token.as_ref() == Some(token))
+                .and_then(|step| step.describe())
+            {
+                if !expected.contains(&terminal) {
+                    expected.push(terminal);
+                }
+            }
+        }
+
+        expected
+    }
+
+    /// The function `closure` replaces every position pointing to a reference to another rule set
+    /// by the first position of each rule of this set, and skips the predicates which hold, until
+    /// only tokens and rule ends remain.
+    ///
+    /// Arguments:
+    ///
+    /// * `positions`: The positions to expand.
+    ///
+    /// Returns:
+    ///
+    /// The expanded positions, without duplicates, in the order of the grammar.
+    pub(crate) fn closure<T: Clone>(positions: Vec<Position<T>>) -> Vec<Position<T>> {
+        Self::expand(positions, &mut Vec::new())
+    }
+
+    /// The function `expand` computes the closure of the given positions (see `closure`), dropping
+    /// the positions whose predicate does not hold.
+    ///
+    /// Arguments:
+    ///
+    /// * `positions`: The positions to expand.
+    /// * `failed`: The predicates which did not hold, in the order they were checked.
+    ///
+    /// Returns:
+    ///
+    /// The expanded positions, without duplicates.
+    fn expand<T: Clone>(
+        positions: Vec<Position<T>>,
+        failed: &mut Vec<Predicate>,
+    ) -> Vec<Position<T>> {
+        // the positions are expanded first in, first out, so that they stay in the order of the
+        // grammar
+        let mut positions = VecDeque::from(positions);
+        let mut visited: Vec<Position<T>> = Vec::new();
+        let mut result = Vec::new();
+
+        while let Some(position) = positions.pop_front() {
+            // skip the positions already seen, rules may reference each other in a loop
+            if visited.iter().any(|p| p.same(&position)) {
+                continue;
+            }
+            visited.push(position.clone());
+
+            let steps = position.steps.lock().unwrap();
+            let step = steps.get(position.index);
+            let next = step.and_then(|step| step.next.clone());
+            let predicate = step.and_then(|step| step.predicate.clone());
+            drop(steps);
+
+            match (next, predicate) {
+                // the position is a reference to another rule set, expand it
+                (Some(rule_set), _) => {
+                    let rule_set = rule_set.lock().unwrap();
+                    for rule in &rule_set.rules {
+                        positions.push_back(Position {
+                            steps: rule.steps.clone(),
+                            index: 0,
+                            values: position.values.as_ref().map(|_| Vec::new()),
+                        });
+                    }
+                }
+                // the position is a predicate, go past it if it holds
+                (None, Some(predicate)) => {
+                    match position.values.as_ref().map(|v| predicate.holds(v)) {
+                        Some(false) => failed.push(predicate),
+                        _ => positions.push_back(Position {
+                            index: position.index + 1,
+                            ..position
+                        }),
+                    }
+                }
+                // the position waits for a token or is the end of a rule
+                (None, None) => result.push(position),
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grammar::{CompiledGrammar, Grammar};
+
+    const CONTACTS: &str = "S -> C\nC -> contact identifier identifier number C | None";
+    const ENDED: &str = "S -> C\nC -> contact identifier identifier number C | end";
+
+    fn compile(text: &str) -> CompiledGrammar {
+        Grammar::from_text(text).unwrap().compile().unwrap()
+    }
+
+    /// Feeds the input to a new validator in chunks of `size` bytes.
+    fn validate(grammar: &CompiledGrammar, input: &str, size: usize) -> Result<(), Error> {
+        let mut validator = grammar.validator();
+        for chunk in input.as_bytes().chunks(size) {
+            validator.feed(std::str::from_utf8(chunk).unwrap())?;
+        }
+        validator.finish()
+    }
+
+    #[test]
+    fn accepts_in_chunks() {
+        let grammar = compile(CONTACTS);
+        let input = "contact a b 10\ncontact b c 20\n";
+
+        for size in 1..=3 {
+            assert!(
+                validate(&grammar, input, size).is_ok(),
+                "chunks of {}",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_end_terminal_in_chunks() {
+        let grammar = compile(ENDED);
+
+        for size in 1..=3 {
+            assert!(validate(&grammar, "contact a b 10", size).is_ok());
+            assert!(validate(&grammar, "contact a b 10\n", size).is_ok());
+        }
+        assert!(grammar.validator().finish().is_ok());
+    }
+
+    #[test]
+    fn rejects_unexpected_token() {
+        let grammar = compile(CONTACTS);
+
+        for size in 1..=3 {
+            let error = validate(&grammar, "contact a 10 b", size).unwrap_err();
+            assert!(
+                matches!(
+                    &error,
+                    Error::Syntax { span, found: Some(Token::Number), expected }
+                        if *span == (10..12) && *expected == vec![Token::Identifier]
+                ),
+                "{:?}",
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let grammar = compile(ENDED);
+
+        let error = validate(&grammar, "contact a b", 2).unwrap_err();
+        assert!(
+            matches!(&error, Error::Syntax { span, found: None, expected }
+                if *span == (11..11) && *expected == vec![Token::Number]),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn analyzes_prefixes() {
+        let grammar = compile(ENDED);
+
+        let prefix = analyze_prefix(grammar.store(), "contact a");
+        assert!(prefix.viable && !prefix.accepted);
+        assert_eq!(prefix.expected, vec![Token::Identifier]);
+
+        let prefix = analyze_prefix(grammar.store(), "contact a b 10");
+        assert!(prefix.viable && prefix.accepted);
+        assert_eq!(prefix.expected, vec![Token::Contact, Token::End]);
+
+        let prefix = analyze_prefix(grammar.store(), "contact 10");
+        assert!(!prefix.viable && !prefix.accepted);
+        assert!(prefix.expected.is_empty());
+    }
+
+    #[test]
+    fn recovers_after_errors() {
+        let grammar = compile(CONTACTS);
+        let mut validator = grammar.validator().with_recovery(vec![Token::Contact]);
+
+        let _ = validator.feed("contact a 10\ncontact b c 20\ncontact d\n");
+        let errors = validator.finish_all();
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(matches!(&errors[0], Error::Syntax { span, .. } if span.start == 10));
+    }
+}